@@ -0,0 +1,164 @@
+use super::observer::{NoObserver, Observer};
+use super::simulated_annealing::Energy;
+use super::termination::Termination;
+use super::traits::Solution;
+use rand::{Rng, rng};
+
+/// Removes parts of a solution, P is the partial solution with the removed parts
+pub trait Destroy<S, P> {
+    fn destroy(&self, solution: &S) -> P;
+}
+
+/// Completes a partial solution
+pub trait Repair<S, P> {
+    fn repair(&self, partial: P) -> S;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AlnsConfig {
+    pub iterations: usize,
+    // Number of iterations between weight updates
+    pub segment_length: usize,
+    // Reaction factor, 0 keeps the weights, 1 only uses the scores of the last segment
+    pub reaction: f64,
+    // Scores for new best solution, better than current solution and accepted solution
+    pub scores: [f64; 3],
+    // Annealing acceptance, temperature 0 accepts only solutions which are not worse
+    pub initial_temperature: f64,
+    pub cooling: f64,
+}
+
+impl Default for AlnsConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 500,
+            segment_length: 50,
+            reaction: 0.2,
+            scores: [33.0, 9.0, 3.0],
+            initial_temperature: 0.05,
+            cooling: 0.995,
+        }
+    }
+}
+
+/// Adaptive weight of one operator
+struct OperatorWeight {
+    weight: f64,
+    score: f64,
+    uses: usize,
+}
+
+impl OperatorWeight {
+    fn new() -> Self {
+        Self { weight: 1.0, score: 0.0, uses: 0 }
+    }
+
+    // End of segment
+    fn update(&mut self, reaction: f64) {
+        if self.uses > 0 {
+            self.weight = (1.0 - reaction) * self.weight + reaction * self.score / self.uses as f64;
+        }
+        self.score = 0.0;
+        self.uses = 0;
+    }
+}
+
+/// Roulette wheel selection on the operator weights
+fn roulette(weights: &[OperatorWeight], rng: &mut impl Rng) -> usize {
+    let total: f64 = weights.iter().map(|w| w.weight).sum();
+    if total <= 0.0 {
+        return rng.random_range(0..weights.len());
+    }
+    let mut ticket = rng.random::<f64>() * total;
+    for (idx, w) in weights.iter().enumerate() {
+        if ticket < w.weight {
+            return idx;
+        }
+        ticket -= w.weight;
+    }
+    weights.len() - 1
+}
+
+/// Adaptive large neighborhood search, returns best solution found
+pub fn solve<S, P, E>(start: S, destroy: &[&dyn Destroy<S, P>], repair: &[&dyn Repair<S, P>], energy: &E, config: &AlnsConfig) -> S
+where
+    S: Solution,
+    E: Energy<S::Cost>,
+{
+    solve_with(start, destroy, repair, energy, config, &Termination::default(), None)
+}
+
+/// Adaptive large neighborhood search with termination, one iteration is one destroy and repair
+pub fn solve_with<S, P, E>(start: S, destroy: &[&dyn Destroy<S, P>], repair: &[&dyn Repair<S, P>], energy: &E, config: &AlnsConfig, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S
+where
+    S: Solution,
+    E: Energy<S::Cost>,
+{
+    assert!(!destroy.is_empty() && !repair.is_empty(), "ALNS needs destroy and repair operators");
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+    let mut rng = rng();
+
+    let mut destroy_weights: Vec<OperatorWeight> = destroy.iter().map(|_| OperatorWeight::new()).collect();
+    let mut repair_weights: Vec<OperatorWeight> = repair.iter().map(|_| OperatorWeight::new()).collect();
+
+    let mut current_cost = start.cost();
+    let mut current_energy = energy.energy(&current_cost);
+    let mut best = start.clone();
+    let mut best_cost = current_cost;
+    let mut current = start;
+    let mut temperature = config.initial_temperature;
+
+    for iteration in 0..config.iterations {
+        if budget.should_stop(&best_cost) {
+            break;
+        }
+        observer.on_iteration_start(budget.iterations, &current);
+        budget.count_iteration();
+
+        let d = roulette(&destroy_weights, &mut rng);
+        let r = roulette(&repair_weights, &mut rng);
+        let candidate = repair[r].repair(destroy[d].destroy(&current));
+        budget.count_evaluation();
+        let candidate_cost = candidate.cost();
+        let candidate_energy = energy.energy(&candidate_cost);
+        observer.on_neighbor_evaluated(candidate_cost < current_cost);
+
+        // Score of the operator pair
+        let delta = candidate_energy - current_energy;
+        let accepted = delta <= 0.0 || (temperature > 0.0 && rng.random::<f64>() < (-delta / temperature).exp());
+        let score = if candidate_cost < best_cost {
+            config.scores[0]
+        } else if candidate_cost < current_cost {
+            config.scores[1]
+        } else if accepted {
+            config.scores[2]
+        } else {
+            0.0
+        };
+        for weight in [&mut destroy_weights[d], &mut repair_weights[r]] {
+            weight.score += score;
+            weight.uses += 1;
+        }
+
+        if candidate_cost < best_cost {
+            best = candidate.clone();
+            best_cost = candidate_cost;
+            observer.on_improvement(&best);
+        }
+        if accepted {
+            current = candidate;
+            current_cost = candidate_cost;
+            current_energy = candidate_energy;
+        }
+        temperature *= config.cooling;
+
+        // Adapt weights at end of segment
+        if (iteration + 1) % config.segment_length.max(1) == 0 {
+            destroy_weights.iter_mut().chain(repair_weights.iter_mut()).for_each(|w| w.update(config.reaction));
+        }
+    }
+    observer.on_finished(&best);
+    best
+}
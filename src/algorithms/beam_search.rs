@@ -0,0 +1,93 @@
+use super::grasp::CandidateList;
+use super::observer::{NoObserver, Observer};
+use super::termination::Termination;
+
+#[derive(Clone, Copy, Debug)]
+pub struct BeamConfig {
+    // Number of partial states kept in every step
+    pub width: usize,
+    // Number of best scored candidates each state is expanded with
+    pub expansions: usize,
+}
+
+impl Default for BeamConfig {
+    fn default() -> Self {
+        Self { width: 5, expansions: 3 }
+    }
+}
+
+/// Beam search over greedy constructions. Candidates are ranked by score (higher first),
+/// partial states by the heuristic (lower is better). Returns best final state
+pub fn solve<P, F, H>(initial: &P, score: F, heuristic: H, config: &BeamConfig) -> P
+where
+    P: CandidateList + Clone,
+    F: Fn(&P::Item) -> f64,
+    H: Fn(&P) -> f64,
+{
+    solve_with(initial, score, heuristic, config, &Termination::<()>::default(), None)
+}
+
+/// Beam search with termination, one iteration is one expansion of the beam.
+/// If the solver is stopped, the remaining items of the best state are added with GreedyState::complete
+pub fn solve_with<P, F, H, C>(initial: &P, score: F, heuristic: H, config: &BeamConfig, termination: &Termination<C>, observer: Option<&mut dyn Observer<P>>) -> P
+where
+    P: CandidateList + Clone,
+    F: Fn(&P::Item) -> f64,
+    H: Fn(&P) -> f64,
+    C: Clone,
+{
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+
+    // Partial states with their heuristic value, sorted best first
+    let mut beam = vec![(initial.clone(), heuristic(initial))];
+
+    while !beam.iter().all(|(state, _)| state.is_finished()) && !budget.is_exhausted() {
+        observer.on_iteration_start(budget.iterations, &beam[0].0);
+        budget.count_iteration();
+
+        let mut next = Vec::with_capacity(beam.len() * config.expansions);
+        let mut expanded = false;
+        for (state, value) in beam {
+            let mut candidates: Vec<(f64, P::Item)> = if state.is_finished() {
+                Vec::new()
+            } else {
+                state.candidates().into_iter().map(|candidate| (score(&candidate), candidate)).collect()
+            };
+            // Finished states and states without candidates stay in the beam
+            if candidates.is_empty() {
+                next.push((state, value));
+                continue;
+            }
+            expanded = true;
+            candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+            for (_, candidate) in candidates.into_iter().take(config.expansions.max(1)) {
+                let mut child = state.clone();
+                child.apply(candidate);
+                budget.count_evaluation();
+                let child_value = heuristic(&child);
+                observer.on_neighbor_evaluated(child_value < value);
+                next.push((child, child_value));
+            }
+        }
+        // Stable sort, so ties keep the order of the parents
+        next.sort_by(|a, b| a.1.total_cmp(&b.1));
+        next.truncate(config.width.max(1));
+        beam = next;
+        if !expanded {
+            break;
+        }
+        observer.on_improvement(&beam[0].0);
+    }
+    // Prefer finished states
+    let mut best = beam.into_iter()
+        .min_by(|a, b| (!a.0.is_finished()).cmp(&!b.0.is_finished()).then(a.1.total_cmp(&b.1)))
+        .map(|(state, _)| state)
+        .expect("beam is never empty");
+    if !best.is_finished() {
+        best.complete();
+    }
+    observer.on_finished(&best);
+    best
+}
@@ -0,0 +1,97 @@
+use super::observer::{NoObserver, Observer};
+use super::termination::Termination;
+use super::traits::Solution;
+use rand::{Rng, rng};
+use rand::rngs::ThreadRng;
+
+/// Solution encoded by a vector of random keys in [0, 1)
+pub trait RandomKeySolution: Solution {
+    fn keys(&self) -> &[f64];
+    // Solution of the same problem with other keys
+    fn with_keys(&self, keys: Vec<f64>) -> Self;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BrkgaConfig {
+    pub population_size: usize,
+    pub generations: usize,
+    // Fraction of best individuals kept as elite
+    pub elite_fraction: f64,
+    // Fraction of new random individuals in every generation
+    pub mutant_fraction: f64,
+    // Probability to inherit a key from the elite parent
+    pub bias: f64,
+}
+
+impl Default for BrkgaConfig {
+    fn default() -> Self {
+        Self { population_size: 50, generations: 50, elite_fraction: 0.2, mutant_fraction: 0.15, bias: 0.7 }
+    }
+}
+
+/// Biased random-key genetic algorithm, template defines the problem and number of keys
+pub fn solve<S: RandomKeySolution>(template: &S, config: &BrkgaConfig) -> S {
+    solve_with(template, config, &Termination::default(), None)
+}
+
+/// BRKGA with termination, one iteration is one generation
+pub fn solve_with<S: RandomKeySolution>(template: &S, config: &BrkgaConfig, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S {
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+    let mut rng = rng();
+
+    let size = config.population_size.max(2);
+    let num_keys = template.keys().len();
+    let num_elite = ((size as f64 * config.elite_fraction) as usize).clamp(1, size - 1);
+    let num_mutants = ((size as f64 * config.mutant_fraction) as usize).min(size - num_elite);
+
+    let random_individual = |rng: &mut ThreadRng| {
+        let individual = template.with_keys((0..num_keys).map(|_| rng.random::<f64>()).collect());
+        let cost = individual.cost();
+        (individual, cost)
+    };
+
+    // Individuals with their cost, sorted best first
+    let mut population: Vec<(S, S::Cost)> = (0..size).map(|_| random_individual(&mut rng)).collect();
+    population.sort_by_key(|(_, cost)| *cost);
+    let mut best = population[0].clone();
+
+    for _ in 0..config.generations {
+        if budget.should_stop(&best.1) {
+            break;
+        }
+        observer.on_iteration_start(budget.iterations, &best.0);
+        budget.count_iteration();
+
+        let mut next: Vec<(S, S::Cost)> = population[..num_elite].to_vec();
+        for _ in 0..num_mutants {
+            budget.count_evaluation();
+            let mutant = random_individual(&mut rng);
+            observer.on_neighbor_evaluated(mutant.1 < best.1);
+            next.push(mutant);
+        }
+        // Biased crossover of an elite and a non elite parent
+        while next.len() < size {
+            let elite = population[rng.random_range(0..num_elite)].0.keys();
+            let other = population[rng.random_range(num_elite..size)].0.keys();
+            let keys = elite.iter().zip(other)
+                .map(|(&e, &o)| if rng.random::<f64>() < config.bias { e } else { o })
+                .collect();
+            let child = template.with_keys(keys);
+            budget.count_evaluation();
+            let cost = child.cost();
+            observer.on_neighbor_evaluated(cost < best.1);
+            next.push((child, cost));
+        }
+        next.sort_by_key(|(_, cost)| *cost);
+        population = next;
+
+        if population[0].1 < best.1 {
+            best = population[0].clone();
+            observer.on_improvement(&best.0);
+        }
+    }
+    observer.on_finished(&best.0);
+    best.0
+}
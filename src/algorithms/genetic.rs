@@ -0,0 +1,130 @@
+use super::observer::{NoObserver, Observer};
+use super::termination::Termination;
+use super::traits::Solution;
+use rand::{Rng, rng};
+
+pub trait Crossover<S> {
+    // Create one child from two parents
+    fn crossover(&self, first: &S, second: &S) -> S;
+}
+
+pub trait Mutation<S> {
+    fn mutate(&self, solution: &mut S);
+}
+
+/// Parent selection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selection {
+    // Best of `size` random individuals
+    Tournament { size: usize },
+    // Roulette wheel on ranks, the best individual has the largest slot
+    Roulette,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GeneticConfig {
+    pub generations: usize,
+    // Number of best individuals copied unchanged into the next generation
+    pub elite: usize,
+    pub crossover_rate: f64,
+    pub mutation_rate: f64,
+    pub selection: Selection,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        Self { generations: 50, elite: 2, crossover_rate: 0.9, mutation_rate: 0.2, selection: Selection::Tournament { size: 3 } }
+    }
+}
+
+/// Genetic algorithm on the given start population, returns best individual found
+pub fn solve<S, X, M>(population: Vec<S>, crossover: &X, mutation: &M, config: &GeneticConfig) -> S
+where
+    S: Solution,
+    X: Crossover<S>,
+    M: Mutation<S>,
+{
+    solve_with(population, crossover, mutation, config, &Termination::default(), None)
+}
+
+/// Genetic algorithm with termination, one iteration is one generation
+pub fn solve_with<S, X, M>(population: Vec<S>, crossover: &X, mutation: &M, config: &GeneticConfig, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S
+where
+    S: Solution,
+    X: Crossover<S>,
+    M: Mutation<S>,
+{
+    assert!(!population.is_empty(), "Population must not be empty");
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+    let mut rng = rng();
+
+    let size = population.len();
+    // Individuals with their cost, sorted best first
+    let mut population: Vec<(S, S::Cost)> = population.into_iter().map(|s| { let c = s.cost(); (s, c) }).collect();
+    population.sort_by_key(|(_, cost)| *cost);
+    let mut best = population[0].clone();
+
+    for _ in 0..config.generations {
+        if budget.should_stop(&best.1) {
+            break;
+        }
+        observer.on_iteration_start(budget.iterations, &best.0);
+        budget.count_iteration();
+
+        // Elitism
+        let mut next: Vec<(S, S::Cost)> = population.iter().take(config.elite.min(size)).cloned().collect();
+        while next.len() < size {
+            let first = &population[select(&population, config.selection, &mut rng)].0;
+            let mut child = if rng.random::<f64>() < config.crossover_rate {
+                let second = &population[select(&population, config.selection, &mut rng)].0;
+                crossover.crossover(first, second)
+            } else {
+                first.clone()
+            };
+            if rng.random::<f64>() < config.mutation_rate {
+                mutation.mutate(&mut child);
+            }
+            budget.count_evaluation();
+            let cost = child.cost();
+            observer.on_neighbor_evaluated(cost < best.1);
+            next.push((child, cost));
+        }
+        next.sort_by_key(|(_, cost)| *cost);
+        population = next;
+
+        if population[0].1 < best.1 {
+            best = population[0].clone();
+            observer.on_improvement(&best.0);
+        }
+    }
+    observer.on_finished(&best.0);
+    best.0
+}
+
+/// Select index of a parent, population must be sorted best first
+fn select<S, C: Ord>(population: &[(S, C)], selection: Selection, rng: &mut impl Rng) -> usize {
+    let n = population.len();
+    match selection {
+        Selection::Tournament { size } => {
+            (0..size.max(1))
+                .map(|_| rng.random_range(0..n))
+                .min_by(|&a, &b| population[a].1.cmp(&population[b].1))
+                .unwrap()
+        }
+        Selection::Roulette => {
+            // Rank i gets weight n - i
+            let total = n * (n + 1) / 2;
+            let mut ticket = rng.random_range(0..total);
+            for idx in 0..n {
+                let weight = n - idx;
+                if ticket < weight {
+                    return idx;
+                }
+                ticket -= weight;
+            }
+            n - 1
+        }
+    }
+}
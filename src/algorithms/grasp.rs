@@ -0,0 +1,117 @@
+use super::greedy;
+use super::local_search::LocalSearchRun;
+use super::observer::{NoObserver, Observer};
+use super::termination::Termination;
+use super::traits::{GreedyState, Neighborhood, SelectionStrategy, Solution};
+use rand::{Rng, rng};
+
+/// Greedy state which can list all items that may be applied next
+pub trait CandidateList: GreedyState {
+    fn candidates(&self) -> Vec<Self::Item>;
+}
+
+/// Restricted candidate list, selects randomly among candidates with
+/// score >= max - alpha * (max - min). alpha = 0 is pure greedy, alpha = 1 pure random
+pub struct RestrictedCandidateStrategy<F> {
+    pub alpha: f64,
+    pub score: F,
+}
+
+impl<F> RestrictedCandidateStrategy<F> {
+    pub fn new(alpha: f64, score: F) -> Self {
+        Self { alpha, score }
+    }
+}
+
+impl<P, F> SelectionStrategy<P> for RestrictedCandidateStrategy<F>
+where
+    P: CandidateList,
+    F: Fn(&P::Item) -> f64,
+{
+    fn next_candidate(&mut self, problem: &P) -> Option<P::Item> {
+        let candidates = problem.candidates();
+        let scores: Vec<f64> = candidates.iter().map(&self.score).collect();
+        let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+        let threshold = max - self.alpha * (max - min);
+
+        let mut restricted: Vec<P::Item> = candidates.into_iter().zip(scores)
+            .filter(|(_, score)| *score >= threshold)
+            .map(|(candidate, _)| candidate)
+            .collect();
+        if restricted.is_empty() {
+            return None;
+        }
+        let idx = rng().random_range(0..restricted.len());
+        Some(restricted.swap_remove(idx))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GraspConfig {
+    // Number of constructions with local search
+    pub starts: usize,
+}
+
+impl Default for GraspConfig {
+    fn default() -> Self {
+        Self { starts: 10 }
+    }
+}
+
+/// Multi-start GRASP: greedy construction from initial state followed by local search, returns best result
+pub fn solve<P, St, S, N>(initial: &P, strategy: &mut St, to_solution: impl Fn(P) -> S, neighborhood: &N, config: &GraspConfig) -> S
+where
+    P: GreedyState + Clone,
+    St: SelectionStrategy<P>,
+    S: Solution,
+    N: Neighborhood<S>,
+{
+    solve_with(initial, strategy, to_solution, neighborhood, config, &Termination::default(), None)
+}
+
+/// GRASP with termination, one iteration is one construction with local search.
+/// Construction and local search share the budget, a stopped construction is completed cheaply
+pub fn solve_with<P, St, S, N>(initial: &P, strategy: &mut St, to_solution: impl Fn(P) -> S, neighborhood: &N, config: &GraspConfig, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S
+where
+    P: GreedyState + Clone,
+    St: SelectionStrategy<P>,
+    S: Solution,
+    N: Neighborhood<S>,
+{
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+    let mut best: Option<(S, S::Cost)> = None;
+
+    for start in 0..config.starts.max(1) {
+        // The first start runs even with an exhausted budget, its construction is completed cheaply
+        if start > 0 && (budget.is_exhausted() || best.as_ref().is_some_and(|(_, cost)| budget.reached_target(cost))) {
+            break;
+        }
+        // Construction
+        let mut state = initial.clone();
+        let construction = greedy::solve_with(&mut state, strategy, &budget.remaining(), None);
+        budget.add_evaluations(construction.evaluations);
+        let constructed = to_solution(state);
+        observer.on_iteration_start(budget.iterations, &constructed);
+        budget.count_iteration();
+
+        // Local search shares the remaining budget
+        let mut run = LocalSearchRun::new(constructed, neighborhood).with_termination(&budget.remaining());
+        while !run.step().is_done() {}
+        budget.add_evaluations(run.budget().evaluations);
+        let cost = run.current_cost();
+        let solution = run.into_solution();
+
+        let improving = best.as_ref().is_none_or(|(_, best_cost)| cost < *best_cost);
+        observer.on_neighbor_evaluated(improving);
+        if improving {
+            observer.on_improvement(&solution);
+            best = Some((solution, cost));
+        }
+    }
+    let (best, _) = best.expect("GRASP runs at least one start");
+    observer.on_finished(&best);
+    best
+}
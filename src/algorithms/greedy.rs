@@ -1,16 +1,112 @@
-use super::traits::{GreedyState, SelectionStrategy};
+use super::observer::{NoObserver, Observer};
+use super::termination::{Budget, Termination};
+use super::traits::{GreedyState, SelectionStrategy, StepOutcome};
 
 pub fn solve<P, S>(problem: &mut P, strategy: &mut S) 
 where 
     P: GreedyState,
     S: SelectionStrategy<P>,
 {
-    // As long as rectangle is left
-    while !problem.is_finished() {
-        if let Some(candidate) = strategy.next_candidate(problem) {
-            problem.apply(candidate);
+    GreedyRun::new(problem, strategy).run();
+}
+
+/// Greedy with termination. If the solver is stopped, the remaining items are added with GreedyState::complete,
+/// so the problem is always finished. The target cost is not checked, a partial state has no cost to compare.
+/// Returns the budget of the run with the used iterations and evaluations
+pub fn solve_with<P, S, C>(problem: &mut P, strategy: &mut S, termination: &Termination<C>, observer: Option<&mut dyn Observer<P>>) -> Budget<C>
+where 
+    P: GreedyState,
+    S: SelectionStrategy<P>,
+    C: Clone,
+{
+    let mut run = GreedyRun::new(problem, strategy).with_termination(termination);
+    if let Some(observer) = observer {
+        run = run.with_observer(observer);
+    }
+    while !run.step().is_done() {}
+    run.budget().clone()
+}
+
+/// Step-wise greedy, every step applies one candidate
+pub struct GreedyRun<'a, P, S, C = ()> {
+    problem: &'a mut P,
+    strategy: &'a mut S,
+    budget: Budget<C>,
+    observer: Box<dyn Observer<P> + 'a>,
+    done: Option<StepOutcome>,
+}
+
+impl<'a, P, S> GreedyRun<'a, P, S>
+where 
+    P: GreedyState,
+    S: SelectionStrategy<P>,
+{
+    pub fn new(problem: &'a mut P, strategy: &'a mut S) -> Self {
+        Self { problem, strategy, budget: Termination::default().start(), observer: Box::new(NoObserver), done: None }
+    }
+}
+
+impl<'a, P, S, C> GreedyRun<'a, P, S, C>
+where 
+    P: GreedyState,
+    S: SelectionStrategy<P>,
+    C: Clone,
+{
+    pub fn with_termination<T: Clone>(self, termination: &Termination<T>) -> GreedyRun<'a, P, S, T> {
+        GreedyRun { problem: self.problem, strategy: self.strategy, budget: termination.start(), observer: self.observer, done: self.done }
+    }
+
+    pub fn with_observer(mut self, observer: &'a mut dyn Observer<P>) -> Self {
+        self.observer = Box::new(observer);
+        self
+    }
+
+    pub fn state(&self) -> &P {
+        self.problem
+    }
+
+    pub fn budget(&self) -> &Budget<C> {
+        &self.budget
+    }
+
+    pub fn step(&mut self) -> StepOutcome {
+        if let Some(outcome) = self.done {
+            return outcome;
+        }
+        // As long as rectangle is left
+        if self.problem.is_finished() {
+            return self.finish(StepOutcome::Finished);
+        }
+        if self.budget.is_exhausted() {
+            self.problem.complete();
+            return self.finish(StepOutcome::Stopped);
+        }
+        self.observer.on_iteration_start(self.budget.iterations, self.problem);
+        self.budget.count_iteration();
+        if let Some(candidate) = self.strategy.next_candidate(self.problem) {
+            self.budget.count_evaluation();
+            self.observer.on_neighbor_evaluated(true);
+            self.problem.apply(candidate);
+            self.observer.on_improvement(self.problem);
+            StepOutcome::Changed
         } else {
-            break;
+            self.finish(StepOutcome::Finished)
         }
     }
+
+    // Step until done
+    pub fn run(mut self) -> StepOutcome {
+        loop {
+            let outcome = self.step();
+            if outcome.is_done() {
+                return outcome;
+            }
+        }
+    }
+
+    fn finish(&mut self, outcome: StepOutcome) -> StepOutcome {
+        self.done = Some(outcome);
+        self.observer.on_finished(self.problem);
+        outcome
+    }
 }
@@ -0,0 +1,120 @@
+use super::local_search::LocalSearchRun;
+use super::observer::{NoObserver, Observer};
+use super::termination::{Budget, Termination};
+use super::traits::{Neighborhood, Solution};
+
+pub trait Perturbation<S> {
+    // Returns a perturbed copy of the solution
+    fn perturb(&self, solution: &S) -> S;
+}
+
+/// Decides from which local optimum the next iteration continues
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Acceptance {
+    // Continue from new local optimum only if it is better
+    Better,
+    // Always continue from new local optimum
+    RandomWalk,
+    // Like Better, but restart from the start solution after `stagnation` iterations without new best
+    Restart { stagnation: usize },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct IlsConfig {
+    pub max_iterations: usize,
+    pub acceptance: Acceptance,
+}
+
+impl Default for IlsConfig {
+    fn default() -> Self {
+        Self { max_iterations: 50, acceptance: Acceptance::Better }
+    }
+}
+
+/// Iterated local search, returns best local optimum found
+pub fn solve<S, N, P>(start: S, neighborhood: &N, perturbation: &P, config: &IlsConfig) -> S
+where
+    S: Solution,
+    N: Neighborhood<S>,
+    P: Perturbation<S>,
+{
+    solve_with(start, neighborhood, perturbation, config, &Termination::default(), None)
+}
+
+/// Iterated local search with termination, one iteration is one perturbation and descent
+pub fn solve_with<S, N, P>(start: S, neighborhood: &N, perturbation: &P, config: &IlsConfig, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S
+where
+    S: Solution,
+    N: Neighborhood<S>,
+    P: Perturbation<S>,
+{
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+
+    // Local search shares the remaining budget
+    let descend = |solution: S, budget: &mut Budget<S::Cost>| {
+        let mut run = LocalSearchRun::new(solution, neighborhood).with_termination(&budget.remaining());
+        while !run.step().is_done() {}
+        budget.add_evaluations(run.budget().evaluations);
+        run.into_solution()
+    };
+
+    let mut current = descend(start.clone(), &mut budget);
+    let mut current_cost = current.cost();
+    let mut best = current.clone();
+    let mut best_cost = current_cost;
+    let mut stagnation = 0;
+
+    for _ in 0..config.max_iterations {
+        if budget.should_stop(&best_cost) {
+            break;
+        }
+        observer.on_iteration_start(budget.iterations, &current);
+        budget.count_iteration();
+
+        let candidate = descend(perturbation.perturb(&current), &mut budget);
+        let candidate_cost = candidate.cost();
+        observer.on_neighbor_evaluated(candidate_cost < current_cost);
+
+        if candidate_cost < best_cost {
+            best = candidate.clone();
+            best_cost = candidate_cost;
+            stagnation = 0;
+            observer.on_improvement(&best);
+        } else {
+            stagnation += 1;
+        }
+        // Acceptance criterion
+        match config.acceptance {
+            Acceptance::Better => {
+                if candidate_cost < current_cost {
+                    current = candidate;
+                    current_cost = candidate_cost;
+                }
+            }
+            Acceptance::RandomWalk => {
+                current = candidate;
+                current_cost = candidate_cost;
+            }
+            Acceptance::Restart { stagnation: max_stagnation } => {
+                if stagnation >= max_stagnation {
+                    // Restart from perturbed start solution
+                    current = descend(perturbation.perturb(&start), &mut budget);
+                    current_cost = current.cost();
+                    stagnation = 0;
+                    if current_cost < best_cost {
+                        best = current.clone();
+                        best_cost = current_cost;
+                        observer.on_improvement(&best);
+                    }
+                } else if candidate_cost < current_cost {
+                    current = candidate;
+                    current_cost = candidate_cost;
+                }
+            }
+        }
+    }
+    observer.on_finished(&best);
+    best
+}
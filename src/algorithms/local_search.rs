@@ -1,28 +1,308 @@
-use super::traits::{Neighborhood, Solution};
+use super::observer::{NoObserver, Observer};
+use super::termination::{Budget, Termination};
+use super::traits::{Move, MoveNeighborhood, Neighborhood, Solution, StepOutcome};
+use std::fmt;
 
-pub fn solve<S, N>(mut current: S, neighborhood: &N) -> S
+/// Rule to select the improving neighbor of a local search step
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PivotRule {
+    // First improving neighbor in order of the neighborhood
+    FirstImprovement,
+    // Best neighbor of the whole neighborhood
+    BestImprovement,
+    // First improving neighbor in random order, neighbors are created in that order
+    RandomFirstImprovement,
+    // Best neighbor of k independently sampled random neighbors, only these are evaluated
+    BestOfSampled(usize),
+}
+
+impl fmt::Display for PivotRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PivotRule::FirstImprovement => write!(f, "First"),
+            PivotRule::BestImprovement => write!(f, "Best"),
+            PivotRule::RandomFirstImprovement => write!(f, "Random First"),
+            PivotRule::BestOfSampled(k) => write!(f, "Best of {}", k),
+        }
+    }
+}
+
+/// Select an improving candidate by pivot rule, a candidate is improving if its key is smaller than threshold.
+/// Candidates must already be in the order and number of the rule, e.g. shuffled or sampled
+fn pivot<T, K, S, C>(
+    rule: PivotRule,
+    candidates: impl Iterator<Item = T>,
+    threshold: K,
+    mut evaluate: impl FnMut(&T) -> K,
+    budget: &mut Budget<C>,
+    observer: &mut dyn Observer<S>,
+) -> Option<(T, K)>
+where
+    K: Ord + Copy,
+{
+    let stop_at_first = matches!(rule, PivotRule::FirstImprovement | PivotRule::RandomFirstImprovement);
+
+    let mut selected: Option<(T, K)> = None;
+    for candidate in candidates {
+        budget.count_evaluation();
+        let key = evaluate(&candidate);
+        let improving = key < threshold;
+        observer.on_neighbor_evaluated(improving);
+        if improving && selected.as_ref().is_none_or(|(_, best)| key < *best) {
+            selected = Some((candidate, key));
+            if stop_at_first {
+                break;
+            }
+        }
+        if budget.is_exhausted() {
+            break;
+        }
+    }
+    selected
+}
+
+pub fn solve<S, N>(current: S, neighborhood: &N) -> S
 where 
     S: Solution,
     N: Neighborhood<S>,
 {
-    loop {
-        let current_cost = current.cost();
-        let mut improved_solution = None;
-        // Search in neighborhood
-        for neighbor in neighborhood.neighbors(&current) {
-            // First Improvement
-            if neighbor.cost() < current_cost {
-                improved_solution = Some(neighbor);
-                break;
-            }
+    LocalSearchRun::new(current, neighborhood).run()
+}
+
+/// Local search with termination, returns the current solution when stopped
+pub fn solve_with<S, N>(current: S, neighborhood: &N, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S
+where 
+    S: Solution,
+    N: Neighborhood<S>,
+{
+    let mut run = LocalSearchRun::new(current, neighborhood).with_termination(termination);
+    if let Some(observer) = observer {
+        run = run.with_observer(observer);
+    }
+    run.run()
+}
+
+/// First improvement on moves, the solution is changed in place and never cloned
+pub fn solve_moves<S, N>(current: S, neighborhood: &N) -> S
+where
+    S: Solution,
+    N: MoveNeighborhood<S>,
+{
+    MoveSearchRun::new(current, neighborhood).run()
+}
+
+/// First improvement on moves with termination
+pub fn solve_moves_with<S, N>(current: S, neighborhood: &N, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S
+where
+    S: Solution,
+    N: MoveNeighborhood<S>,
+{
+    let mut run = MoveSearchRun::new(current, neighborhood).with_termination(termination);
+    if let Some(observer) = observer {
+        run = run.with_observer(observer);
+    }
+    run.run()
+}
+
+/// Step-wise local search, every step moves to an improving neighbor
+pub struct LocalSearchRun<'a, S: Solution, N: ?Sized> {
+    current: S,
+    current_cost: S::Cost,
+    neighborhood: &'a N,
+    pivot_rule: PivotRule,
+    budget: Budget<S::Cost>,
+    observer: Box<dyn Observer<S> + 'a>,
+    done: Option<StepOutcome>,
+}
+
+impl<'a, S, N> LocalSearchRun<'a, S, N>
+where 
+    S: Solution,
+    N: Neighborhood<S> + ?Sized,
+{
+    pub fn new(start: S, neighborhood: &'a N) -> Self {
+        let current_cost = start.cost();
+        Self::with_cost(start, current_cost, neighborhood)
+    }
+
+    // Start solution with known cost, it is not evaluated again
+    pub fn with_cost(start: S, current_cost: S::Cost, neighborhood: &'a N) -> Self {
+        Self { current: start, current_cost, neighborhood, pivot_rule: PivotRule::FirstImprovement, budget: Termination::default().start(), observer: Box::new(NoObserver), done: None }
+    }
+
+    pub fn with_termination(mut self, termination: &Termination<S::Cost>) -> Self {
+        self.budget = termination.start();
+        self
+    }
+
+    pub fn with_observer(mut self, observer: &'a mut dyn Observer<S>) -> Self {
+        self.observer = Box::new(observer);
+        self
+    }
+
+    pub fn with_pivot_rule(mut self, rule: PivotRule) -> Self {
+        self.pivot_rule = rule;
+        self
+    }
+
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    pub fn current_cost(&self) -> S::Cost {
+        self.current_cost
+    }
+
+    pub fn budget(&self) -> &Budget<S::Cost> {
+        &self.budget
+    }
+
+    pub fn step(&mut self) -> StepOutcome {
+        if let Some(outcome) = self.done {
+            return outcome;
         }
+        if self.budget.should_stop(&self.current_cost) {
+            return self.finish(StepOutcome::Stopped);
+        }
+        self.observer.on_iteration_start(self.budget.iterations, &self.current);
+        self.budget.count_iteration();
+        // Search in neighborhood, random rules sample neighbors instead of enumerating all
+        let current = &self.current;
+        let neighborhood = self.neighborhood;
+        let neighbors: Box<dyn Iterator<Item = S>> = match self.pivot_rule {
+            PivotRule::FirstImprovement | PivotRule::BestImprovement => neighborhood.neighbors(current),
+            PivotRule::RandomFirstImprovement => neighborhood.shuffled_neighbors(current),
+            PivotRule::BestOfSampled(k) => Box::new((0..k).filter_map(move |_| neighborhood.random_neighbor(current))),
+        };
+        let improved_solution = pivot(
+            self.pivot_rule,
+            neighbors,
+            self.current_cost,
+            |neighbor| neighbor.cost(),
+            &mut self.budget,
+            &mut *self.observer,
+        );
         // Apply improvement if found
-        if let Some(improvement) = improved_solution {
-            current = improvement;
-        } else {
-            break;
+        match improved_solution {
+            Some((improvement, cost)) => {
+                self.current = improvement;
+                self.current_cost = cost;
+                self.observer.on_improvement(&self.current);
+                StepOutcome::Changed
+            }
+            None if self.budget.is_exhausted() => self.finish(StepOutcome::Stopped),
+            None => self.finish(StepOutcome::Finished),
         }
     }
-    current
+
+    // Step until done and return the final solution
+    pub fn run(mut self) -> S {
+        while !self.step().is_done() {}
+        self.current
+    }
+
+    pub fn into_solution(self) -> S {
+        self.current
+    }
+
+    fn finish(&mut self, outcome: StepOutcome) -> StepOutcome {
+        self.done = Some(outcome);
+        self.observer.on_finished(&self.current);
+        outcome
+    }
 }
 
+/// Step-wise local search on moves, every step applies an improving move in place
+pub struct MoveSearchRun<'a, S: Solution, N> {
+    current: S,
+    neighborhood: &'a N,
+    pivot_rule: PivotRule,
+    budget: Budget<S::Cost>,
+    observer: Box<dyn Observer<S> + 'a>,
+    done: Option<StepOutcome>,
+}
+
+impl<'a, S, N> MoveSearchRun<'a, S, N>
+where 
+    S: Solution,
+    N: MoveNeighborhood<S>,
+{
+    pub fn new(start: S, neighborhood: &'a N) -> Self {
+        Self { current: start, neighborhood, pivot_rule: PivotRule::FirstImprovement, budget: Termination::default().start(), observer: Box::new(NoObserver), done: None }
+    }
+
+    pub fn with_termination(mut self, termination: &Termination<S::Cost>) -> Self {
+        self.budget = termination.start();
+        self
+    }
+
+    pub fn with_observer(mut self, observer: &'a mut dyn Observer<S>) -> Self {
+        self.observer = Box::new(observer);
+        self
+    }
+
+    pub fn with_pivot_rule(mut self, rule: PivotRule) -> Self {
+        self.pivot_rule = rule;
+        self
+    }
+
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    pub fn budget(&self) -> &Budget<S::Cost> {
+        &self.budget
+    }
+
+    pub fn step(&mut self) -> StepOutcome {
+        if let Some(outcome) = self.done {
+            return outcome;
+        }
+        // Cost is only needed for a target cost
+        if self.budget.is_exhausted() || (self.budget.has_target() && self.budget.reached_target(&self.current.cost())) {
+            return self.finish(StepOutcome::Stopped);
+        }
+        self.observer.on_iteration_start(self.budget.iterations, &self.current);
+        self.budget.count_iteration();
+        let current = &self.current;
+        let moves = match self.pivot_rule {
+            PivotRule::FirstImprovement | PivotRule::BestImprovement => self.neighborhood.moves(current),
+            PivotRule::RandomFirstImprovement => self.neighborhood.shuffled_moves(current),
+            PivotRule::BestOfSampled(k) => self.neighborhood.sampled_moves(current, k),
+        };
+        let improving_move = pivot(
+            self.pivot_rule,
+            moves,
+            Default::default(),
+            |mv| mv.delta_cost(current),
+            &mut self.budget,
+            &mut *self.observer,
+        );
+        // Apply improvement if found
+        match improving_move {
+            Some((mut mv, _)) => {
+                mv.apply(&mut self.current);
+                self.observer.on_improvement(&self.current);
+                StepOutcome::Changed
+            }
+            None if self.budget.is_exhausted() => self.finish(StepOutcome::Stopped),
+            None => self.finish(StepOutcome::Finished),
+        }
+    }
+
+    // Step until done and return the final solution
+    pub fn run(mut self) -> S {
+        while !self.step().is_done() {}
+        self.current
+    }
+
+    pub fn into_solution(self) -> S {
+        self.current
+    }
+
+    fn finish(&mut self, outcome: StepOutcome) -> StepOutcome {
+        self.done = Some(outcome);
+        self.observer.on_finished(&self.current);
+        outcome
+    }
+}
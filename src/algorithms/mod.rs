@@ -1,4 +1,15 @@
 pub mod traits;
+pub mod alns;
+pub mod beam_search;
+pub mod brkga;
+pub mod genetic;
+pub mod grasp;
 pub mod greedy;
+pub mod iterated_local_search;
 pub mod local_search;
+pub mod observer;
+pub mod simulated_annealing;
+pub mod tabu_search;
+pub mod termination;
+pub mod variable_neighborhood_search;
 
@@ -0,0 +1,141 @@
+use super::traits::Solution;
+use log::{debug, info, trace};
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+/// Callbacks while a solver is running, all callbacks default to doing nothing
+pub trait Observer<S> {
+    fn on_iteration_start(&mut self, _iteration: usize, _current: &S) {}
+    // Called for every evaluated neighbor or candidate, improving if it is better than the current solution
+    fn on_neighbor_evaluated(&mut self, _improving: bool) {}
+    fn on_improvement(&mut self, _solution: &S) {}
+    fn on_finished(&mut self, _best: &S) {}
+}
+
+/// Observer used when none is passed to a solver
+pub struct NoObserver;
+
+impl<S> Observer<S> for NoObserver {}
+
+// Allows passing observers by reference, e.g. in a pair
+impl<S, O: Observer<S> + ?Sized> Observer<S> for &mut O {
+    fn on_iteration_start(&mut self, iteration: usize, current: &S) {
+        (**self).on_iteration_start(iteration, current);
+    }
+    fn on_neighbor_evaluated(&mut self, improving: bool) {
+        (**self).on_neighbor_evaluated(improving);
+    }
+    fn on_improvement(&mut self, solution: &S) {
+        (**self).on_improvement(solution);
+    }
+    fn on_finished(&mut self, best: &S) {
+        (**self).on_finished(best);
+    }
+}
+
+// Combine two observers
+impl<S, A: Observer<S>, B: Observer<S>> Observer<S> for (A, B) {
+    fn on_iteration_start(&mut self, iteration: usize, current: &S) {
+        self.0.on_iteration_start(iteration, current);
+        self.1.on_iteration_start(iteration, current);
+    }
+    fn on_neighbor_evaluated(&mut self, improving: bool) {
+        self.0.on_neighbor_evaluated(improving);
+        self.1.on_neighbor_evaluated(improving);
+    }
+    fn on_improvement(&mut self, solution: &S) {
+        self.0.on_improvement(solution);
+        self.1.on_improvement(solution);
+    }
+    fn on_finished(&mut self, best: &S) {
+        self.0.on_finished(best);
+        self.1.on_finished(best);
+    }
+}
+
+/// Logs solver progress through the log crate
+pub struct LogObserver {
+    pub name: String,
+}
+
+impl LogObserver {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string() }
+    }
+}
+
+impl<S> Observer<S> for LogObserver
+where
+    S: Solution,
+    S::Cost: Debug,
+{
+    fn on_iteration_start(&mut self, iteration: usize, current: &S) {
+        debug!("{}: iteration {} with cost {:?}", self.name, iteration, current.cost());
+    }
+    fn on_neighbor_evaluated(&mut self, improving: bool) {
+        trace!("{}: neighbor evaluated, improving: {}", self.name, improving);
+    }
+    fn on_improvement(&mut self, solution: &S) {
+        info!("{}: improved to cost {:?}", self.name, solution.cost());
+    }
+    fn on_finished(&mut self, best: &S) {
+        info!("{}: finished with cost {:?}", self.name, best.cost());
+    }
+}
+
+/// Records cost over time and number of evaluations at every improvement
+pub struct ConvergenceRecorder<C> {
+    start: Instant,
+    evaluations: usize,
+    // (time since start, evaluations, cost)
+    pub curve: Vec<(Duration, usize, C)>,
+}
+
+impl<C> ConvergenceRecorder<C> {
+    pub fn new() -> Self {
+        Self { start: Instant::now(), evaluations: 0, curve: Vec::new() }
+    }
+}
+
+impl<C> Default for ConvergenceRecorder<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Solution> Observer<S> for ConvergenceRecorder<S::Cost> {
+    fn on_iteration_start(&mut self, iteration: usize, current: &S) {
+        // Starting point of the curve
+        if iteration == 0 && self.curve.is_empty() {
+            self.curve.push((self.start.elapsed(), self.evaluations, current.cost()));
+        }
+    }
+    fn on_neighbor_evaluated(&mut self, _improving: bool) {
+        self.evaluations += 1;
+    }
+    fn on_improvement(&mut self, solution: &S) {
+        self.curve.push((self.start.elapsed(), self.evaluations, solution.cost()));
+    }
+}
+
+/// Counts iterations, evaluations, improving evaluations and accepted improvements
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EvaluationCounter {
+    pub iterations: usize,
+    pub evaluations: usize,
+    pub improving: usize,
+    pub improvements: usize,
+}
+
+impl<S> Observer<S> for EvaluationCounter {
+    fn on_iteration_start(&mut self, _iteration: usize, _current: &S) {
+        self.iterations += 1;
+    }
+    fn on_neighbor_evaluated(&mut self, improving: bool) {
+        self.evaluations += 1;
+        if improving { self.improving += 1; }
+    }
+    fn on_improvement(&mut self, _solution: &S) {
+        self.improvements += 1;
+    }
+}
@@ -0,0 +1,150 @@
+use super::observer::{NoObserver, Observer};
+use super::termination::Termination;
+use super::traits::{Neighborhood, Solution};
+use rand::{Rng, rng};
+
+/// Converts a (lexicographic) cost into a scalar energy for the acceptance probability
+pub trait Energy<C> {
+    fn energy(&self, cost: &C) -> f64;
+}
+
+// Closures can be used directly as energy function
+impl<C, F: Fn(&C) -> f64> Energy<C> for F {
+    fn energy(&self, cost: &C) -> f64 {
+        self(cost)
+    }
+}
+
+/// Energy for the (number of boxes, score) cost of the rectangle solutions
+pub struct LexicographicEnergy {
+    pub box_weight: f64,
+    pub score_scale: f64,
+}
+
+impl LexicographicEnergy {
+    // Scale score by the squared box area, so every box contributes 1 - (used area / box area)^2
+    pub fn new(box_area: u64) -> Self {
+        Self { box_weight: 1.0, score_scale: 1.0 / (box_area as f64).powi(2) }
+    }
+}
+
+impl Energy<(usize, i64)> for LexicographicEnergy {
+    fn energy(&self, cost: &(usize, i64)) -> f64 {
+        cost.0 as f64 * self.box_weight + cost.1 as f64 * self.score_scale
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum CoolingSchedule {
+    // T = alpha * T
+    Geometric { alpha: f64 },
+    // T = T - delta
+    Linear { delta: f64 },
+    // Geometric cooling, temperature is reset to start temperature when the best
+    // solution did not improve for `stagnation_steps` temperature levels
+    Reheating { alpha: f64, stagnation_steps: usize, max_reheats: usize },
+}
+
+impl CoolingSchedule {
+    fn cool(&self, temperature: f64) -> f64 {
+        match *self {
+            CoolingSchedule::Geometric { alpha } | CoolingSchedule::Reheating { alpha, .. } => temperature * alpha,
+            CoolingSchedule::Linear { delta } => temperature - delta,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AnnealingConfig {
+    pub initial_temperature: f64,
+    pub min_temperature: f64,
+    pub iterations_per_temperature: usize,
+    pub cooling: CoolingSchedule,
+}
+
+impl Default for AnnealingConfig {
+    fn default() -> Self {
+        Self {
+            initial_temperature: 0.5,
+            min_temperature: 0.001,
+            iterations_per_temperature: 100,
+            cooling: CoolingSchedule::Geometric { alpha: 0.95 },
+        }
+    }
+}
+
+/// Simulated annealing, returns best solution found
+pub fn solve<S, N, E>(start: S, neighborhood: &N, energy: &E, config: &AnnealingConfig) -> S
+where
+    S: Solution,
+    N: Neighborhood<S>,
+    E: Energy<S::Cost>,
+{
+    solve_with(start, neighborhood, energy, config, &Termination::default(), None)
+}
+
+/// Simulated annealing with termination, one iteration is one temperature level
+pub fn solve_with<S, N, E>(start: S, neighborhood: &N, energy: &E, config: &AnnealingConfig, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S
+where
+    S: Solution,
+    N: Neighborhood<S>,
+    E: Energy<S::Cost>,
+{
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+    let mut rng = rng();
+    let mut best_cost = start.cost();
+    let mut best = start.clone();
+    let mut current_energy = energy.energy(&best_cost);
+    let mut current = start;
+
+    let mut temperature = config.initial_temperature;
+    let mut stagnation = 0;
+    let mut reheats = 0;
+
+    while !budget.should_stop(&best_cost) {
+        observer.on_iteration_start(budget.iterations, &current);
+        budget.count_iteration();
+        let mut best_improved = false;
+        for _ in 0..config.iterations_per_temperature {
+            if budget.should_stop(&best_cost) {
+                break;
+            }
+            // Stop level if neighborhood is empty
+            let Some(neighbor) = neighborhood.random_neighbor(&current) else { break };
+            budget.count_evaluation();
+            let neighbor_cost = neighbor.cost();
+            let neighbor_energy = energy.energy(&neighbor_cost);
+            // Metropolis criterion
+            let delta = neighbor_energy - current_energy;
+            observer.on_neighbor_evaluated(delta < 0.0);
+            if delta <= 0.0 || rng.random::<f64>() < (-delta / temperature).exp() {
+                if neighbor_cost < best_cost {
+                    best_cost = neighbor_cost;
+                    best = neighbor.clone();
+                    best_improved = true;
+                    observer.on_improvement(&best);
+                }
+                current = neighbor;
+                current_energy = neighbor_energy;
+            }
+        }
+
+        temperature = config.cooling.cool(temperature);
+        stagnation = if best_improved { 0 } else { stagnation + 1 };
+
+        if let CoolingSchedule::Reheating { stagnation_steps, max_reheats, .. } = config.cooling {
+            if reheats < max_reheats && (stagnation >= stagnation_steps || temperature <= config.min_temperature) {
+                temperature = config.initial_temperature;
+                stagnation = 0;
+                reheats += 1;
+            }
+        }
+        if temperature <= config.min_temperature {
+            break;
+        }
+    }
+    observer.on_finished(&best);
+    best
+}
@@ -0,0 +1,191 @@
+use super::observer::{NoObserver, Observer};
+use super::termination::Termination;
+use super::traits::{AttributedNeighborhood, Solution};
+use std::collections::{HashMap, VecDeque};
+
+/// Decides when a tabu neighbor is allowed anyway
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aspiration {
+    // Tabu neighbors are never accepted
+    None,
+    // Tabu neighbors are accepted if they improve the best solution
+    BestCost,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TabuConfig {
+    // Number of iterations an attribute stays tabu
+    pub tenure: usize,
+    pub max_iterations: usize,
+    // Stop after this many iterations without new best solution
+    pub max_stagnation: Option<usize>,
+    pub aspiration: Aspiration,
+}
+
+impl Default for TabuConfig {
+    fn default() -> Self {
+        Self { tenure: 10, max_iterations: 100, max_stagnation: None, aspiration: Aspiration::BestCost }
+    }
+}
+
+/// Tabu search, returns best solution found
+pub fn solve<S, N>(start: S, neighborhood: &N, config: &TabuConfig) -> S
+where
+    S: Solution,
+    N: AttributedNeighborhood<S>,
+{
+    solve_with(start, neighborhood, config, &Termination::default(), None)
+}
+
+/// Tabu search with termination in addition to the configured iteration limits
+pub fn solve_with<S, N>(start: S, neighborhood: &N, config: &TabuConfig, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S
+where
+    S: Solution,
+    N: AttributedNeighborhood<S>,
+{
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+    // Attributes dropped by recent moves, they must not be added again
+    let mut tabu_list: VecDeque<N::Attribute> = VecDeque::with_capacity(config.tenure + 1);
+    // Occurrences of the attributes in the tabu list for constant time lookup
+    let mut tabu_count: HashMap<N::Attribute, usize> = HashMap::new();
+    let mut best_cost = start.cost();
+    let mut best = start.clone();
+    let mut current = start;
+    let mut stagnation = 0;
+
+    for _ in 0..config.max_iterations {
+        let current_cost = current.cost();
+        if budget.should_stop(&best_cost) {
+            break;
+        }
+        observer.on_iteration_start(budget.iterations, &current);
+        budget.count_iteration();
+        // Best admissible neighbor, even if it is worse than current solution
+        let mut selected: Option<(N::Attribute, S, S::Cost)> = None;
+        for (attributes, neighbor) in neighborhood.attributed_neighbors(&current) {
+            if budget.is_exhausted() {
+                break;
+            }
+            budget.count_evaluation();
+            let cost = neighbor.cost();
+            observer.on_neighbor_evaluated(cost < current_cost);
+            let is_tabu = tabu_count.contains_key(&attributes.added);
+            let aspirated = config.aspiration == Aspiration::BestCost && cost < best_cost;
+            if is_tabu && !aspirated {
+                continue;
+            }
+            if selected.as_ref().is_none_or(|(_, _, selected_cost)| cost < *selected_cost) {
+                selected = Some((attributes.dropped, neighbor, cost));
+            }
+        }
+        // All neighbors are tabu
+        let Some((dropped, neighbor, cost)) = selected else { break };
+
+        *tabu_count.entry(dropped.clone()).or_insert(0) += 1;
+        tabu_list.push_back(dropped);
+        if tabu_list.len() > config.tenure {
+            if let Some(expired) = tabu_list.pop_front() {
+                if let Some(count) = tabu_count.get_mut(&expired) {
+                    *count -= 1;
+                    if *count == 0 {
+                        tabu_count.remove(&expired);
+                    }
+                }
+            }
+        }
+        if cost < best_cost {
+            best_cost = cost;
+            best = neighbor.clone();
+            stagnation = 0;
+            observer.on_improvement(&best);
+        } else {
+            stagnation += 1;
+            if config.max_stagnation.is_some_and(|max| stagnation >= max) {
+                break;
+            }
+        }
+        current = neighbor;
+    }
+    observer.on_finished(&best);
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::traits::{MoveAttributes, Neighborhood};
+    use std::cell::RefCell;
+
+    // Three bits with a cost per bit pattern, index bit 0 + 2 * bit 1 + 4 * bit 2
+    #[derive(Clone, Debug)]
+    struct Bits {
+        bits: usize,
+        costs: [i32; 8],
+    }
+
+    impl Solution for Bits {
+        type Cost = i32;
+
+        fn cost(&self) -> i32 {
+            self.costs[self.bits]
+        }
+    }
+
+    // Flip one bit, attributes are (bit, value). Records the solutions whose neighbors are searched
+    #[derive(Default)]
+    struct FlipBit {
+        trajectory: RefCell<Vec<usize>>,
+    }
+
+    impl Neighborhood<Bits> for FlipBit {
+        fn neighbors<'a>(&'a self, solution: &'a Bits) -> Box<dyn Iterator<Item = Bits> + 'a> {
+            Box::new(self.attributed_neighbors(solution).map(|(_, neighbor)| neighbor))
+        }
+    }
+
+    impl AttributedNeighborhood<Bits> for FlipBit {
+        type Attribute = (usize, bool);
+
+        fn attributed_neighbors<'a>(&'a self, solution: &'a Bits) -> Box<dyn Iterator<Item = (MoveAttributes<(usize, bool)>, Bits)> + 'a> {
+            self.trajectory.borrow_mut().push(solution.bits);
+            Box::new((0..3).map(move |bit| {
+                let value = solution.bits & (1 << bit) != 0;
+                let attributes = MoveAttributes { dropped: (bit, value), added: (bit, !value) };
+                (attributes, Bits { bits: solution.bits ^ (1 << bit), ..solution.clone() })
+            }))
+        }
+    }
+
+    // Search walks 000 -> 001 -> 011 -> 111, after that every neighbor sets a bit back to a dropped value
+    fn run(costs: [i32; 8], aspiration: Aspiration, max_iterations: usize) -> (Bits, Vec<usize>) {
+        let config = TabuConfig { tenure: 10, max_iterations, max_stagnation: None, aspiration };
+        let neighborhood = FlipBit::default();
+        let best = solve(Bits { bits: 0, costs }, &neighborhood, &config);
+        (best, neighborhood.trajectory.into_inner())
+    }
+
+    #[test]
+    fn tabu_move_better_than_best_is_aspirated() {
+        // Tabu neighbor 110 of 111 is better than the best solution
+        let costs = [10, 9, 12, 8, 12, 12, 1, 7];
+        let (best, trajectory) = run(costs, Aspiration::BestCost, 10);
+        assert_eq!(trajectory[..4], [0b000, 0b001, 0b011, 0b111]);
+        assert_eq!(best.bits, 0b110);
+
+        // Without aspiration the search stops in 111, all its neighbors are tabu
+        let (best, trajectory) = run(costs, Aspiration::None, 10);
+        assert_eq!(trajectory, [0b000, 0b001, 0b011, 0b111]);
+        assert_eq!(best.bits, 0b111);
+    }
+
+    #[test]
+    fn tabu_move_not_better_than_best_is_rejected() {
+        // From 011 the tabu neighbors 001 and 010 are better than 111 but not better than the best 011
+        let costs = [10, 9, 9, 8, 12, 12, 12, 20];
+        let (best, trajectory) = run(costs, Aspiration::BestCost, 4);
+        assert_eq!(trajectory, [0b000, 0b001, 0b011, 0b111]);
+        assert_eq!(best.bits, 0b011);
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Stopping criteria shared by all solvers, unset criteria never stop a solver
+#[derive(Clone, Debug)]
+pub struct Termination<C> {
+    pub max_duration: Option<Duration>,
+    pub max_iterations: Option<usize>,
+    // Number of evaluated neighbors or candidates
+    pub max_evaluations: Option<usize>,
+    // Stop as soon as the best cost is less or equal
+    pub target_cost: Option<C>,
+    // Can be set from another thread to cancel the solver
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl<C> Default for Termination<C> {
+    fn default() -> Self {
+        Self { max_duration: None, max_iterations: None, max_evaluations: None, target_cost: None, cancel: None }
+    }
+}
+
+impl<C: Clone> Termination<C> {
+    pub fn with_max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    pub fn with_max_iterations(mut self, iterations: usize) -> Self {
+        self.max_iterations = Some(iterations);
+        self
+    }
+
+    pub fn with_max_evaluations(mut self, evaluations: usize) -> Self {
+        self.max_evaluations = Some(evaluations);
+        self
+    }
+
+    pub fn with_target_cost(mut self, cost: C) -> Self {
+        self.target_cost = Some(cost);
+        self
+    }
+
+    pub fn with_cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    // Start measuring time and counting iterations and evaluations
+    pub fn start(&self) -> Budget<C> {
+        Budget { termination: self.clone(), start: Instant::now(), iterations: 0, evaluations: 0 }
+    }
+}
+
+/// Running state of a termination, created by Termination::start
+#[derive(Clone, Debug)]
+pub struct Budget<C> {
+    termination: Termination<C>,
+    start: Instant,
+    pub iterations: usize,
+    pub evaluations: usize,
+}
+
+impl<C> Budget<C> {
+    pub fn count_iteration(&mut self) {
+        self.iterations += 1;
+    }
+
+    pub fn count_evaluation(&mut self) {
+        self.evaluations += 1;
+    }
+
+    // Add evaluations of a nested solver
+    pub fn add_evaluations(&mut self, evaluations: usize) {
+        self.evaluations += evaluations;
+    }
+
+    pub fn has_target(&self) -> bool {
+        self.termination.target_cost.is_some()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    // True if time, iterations or evaluations are used up or the solver was cancelled
+    pub fn is_exhausted(&self) -> bool {
+        let t = &self.termination;
+        t.max_iterations.is_some_and(|max| self.iterations >= max)
+            || t.max_evaluations.is_some_and(|max| self.evaluations >= max)
+            || t.max_duration.is_some_and(|max| self.start.elapsed() >= max)
+            || t.cancel.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+}
+
+impl<C: Clone> Budget<C> {
+    // Termination for a nested solver with the remaining time and evaluations
+    pub fn remaining(&self) -> Termination<C> {
+        let t = &self.termination;
+        Termination {
+            max_duration: t.max_duration.map(|max| max.saturating_sub(self.start.elapsed())),
+            max_iterations: None,
+            max_evaluations: t.max_evaluations.map(|max| max.saturating_sub(self.evaluations)),
+            target_cost: t.target_cost.clone(),
+            cancel: t.cancel.clone(),
+        }
+    }
+}
+
+impl<C: Ord> Budget<C> {
+    pub fn reached_target(&self, best_cost: &C) -> bool {
+        self.termination.target_cost.as_ref().is_some_and(|target| best_cost <= target)
+    }
+
+    pub fn should_stop(&self, best_cost: &C) -> bool {
+        self.reached_target(best_cost) || self.is_exhausted()
+    }
+}
@@ -1,3 +1,6 @@
+use rand::rng;
+use rand::seq::{IteratorRandom, SliceRandom};
+use std::hash::Hash;
 
 pub trait Solution: Clone {
     // Values of cost must be comparable and copyable
@@ -9,6 +12,87 @@ pub trait Solution: Clone {
 pub trait Neighborhood<S> {
     // Returns a iterator over neighbor solutions, lifetime 'a binds it to input data
     fn neighbors<'a>(&'a self, solution: &'a S) -> Box<dyn Iterator<Item = S> + 'a>;
+
+    // Returns one random neighbor, default walks through the whole neighborhood
+    fn random_neighbor(&self, solution: &S) -> Option<S> {
+        self.neighbors(solution).choose(&mut rng())
+    }
+
+    // Returns all neighbors in random order, default collects and shuffles the whole neighborhood
+    fn shuffled_neighbors<'a>(&'a self, solution: &'a S) -> Box<dyn Iterator<Item = S> + 'a> {
+        let mut neighbors: Vec<S> = self.neighbors(solution).collect();
+        neighbors.shuffle(&mut rng());
+        Box::new(neighbors.into_iter())
+    }
+}
+
+/// Attributes of the move that produced a neighbor
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MoveAttributes<A> {
+    // Attribute removed from the solution, e.g. rect X left box Y
+    pub dropped: A,
+    // Attribute added to the solution, e.g. rect X entered box Z
+    pub added: A,
+}
+
+pub trait AttributedNeighborhood<S>: Neighborhood<S> {
+    type Attribute: Clone + Eq + Hash;
+
+    // Returns neighbors together with the attributes of the move that produced them
+    fn attributed_neighbors<'a>(&'a self, solution: &'a S) -> Box<dyn Iterator<Item = (MoveAttributes<Self::Attribute>, S)> + 'a>;
+}
+
+/// Move that changes a solution in place
+pub trait Move<S: Solution> {
+    // Cost difference of the move, improving if smaller than Delta::default()
+    type Delta: Ord + Copy + Default;
+
+    // Cost change if the move would be applied to solution
+    fn delta_cost(&self, solution: &S) -> Self::Delta;
+    fn apply(&mut self, solution: &mut S);
+    // Revert apply, solution must be unchanged since apply
+    fn undo(&mut self, solution: &mut S);
+}
+
+pub trait MoveNeighborhood<S: Solution> {
+    type Move: Move<S>;
+
+    // Returns a iterator over moves, moves are only valid for the solution they were created for
+    fn moves<'a>(&'a self, solution: &'a S) -> Box<dyn Iterator<Item = Self::Move> + 'a>;
+
+    // Returns one random move, default walks through all moves
+    fn random_move(&self, solution: &S) -> Option<Self::Move> {
+        self.moves(solution).choose(&mut rng())
+    }
+
+    // Returns up to k independently sampled random moves, default calls random_move k times
+    fn sampled_moves<'a>(&'a self, solution: &'a S, k: usize) -> Box<dyn Iterator<Item = Self::Move> + 'a> {
+        Box::new((0..k).filter_map(move |_| self.random_move(solution)))
+    }
+
+    // Returns all moves in random order, default collects and shuffles all moves
+    fn shuffled_moves<'a>(&'a self, solution: &'a S) -> Box<dyn Iterator<Item = Self::Move> + 'a> {
+        let mut moves: Vec<Self::Move> = self.moves(solution).collect();
+        moves.shuffle(&mut rng());
+        Box::new(moves.into_iter())
+    }
+}
+
+/// Result of a single step of a step-wise solver run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepOutcome {
+    // Current solution changed
+    Changed,
+    // Solver stopped naturally, e.g. local optimum reached
+    Finished,
+    // Solver stopped by its termination criteria
+    Stopped,
+}
+
+impl StepOutcome {
+    pub fn is_done(&self) -> bool {
+        *self != StepOutcome::Changed
+    }
 }
 
 pub trait GreedyState {
@@ -17,6 +101,8 @@ pub trait GreedyState {
     fn is_finished(&self) -> bool;
     // Add an item to current solution
     fn apply(&mut self, item: Self::Item);
+    // Add all remaining items in a cheap way, used when a solver is stopped before the state is finished
+    fn complete(&mut self);
 }
 
 pub trait SelectionStrategy<P: GreedyState> {
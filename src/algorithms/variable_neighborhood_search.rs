@@ -0,0 +1,128 @@
+use super::local_search::{LocalSearchRun, PivotRule};
+use super::observer::{NoObserver, Observer};
+use super::termination::{Budget, Termination};
+use super::traits::{Neighborhood, Solution, StepOutcome};
+
+#[derive(Clone, Copy, Debug)]
+pub struct VnsConfig {
+    // Number of passes over all neighborhoods
+    pub max_iterations: usize,
+    // Number of random moves in the shaking phase
+    pub shake_strength: usize,
+    // Pivot rule of the descent steps
+    pub pivot_rule: PivotRule,
+}
+
+impl Default for VnsConfig {
+    fn default() -> Self {
+        Self { max_iterations: 20, shake_strength: 1, pivot_rule: PivotRule::FirstImprovement }
+    }
+}
+
+/// Variable neighborhood descent with first improvement, returns a local optimum of all neighborhoods
+pub fn vnd<S: Solution>(start: S, neighborhoods: &[&dyn Neighborhood<S>]) -> S {
+    vnd_with(start, neighborhoods, PivotRule::FirstImprovement, &Termination::default(), None)
+}
+
+/// Variable neighborhood descent with pivot rule and termination, one iteration is one local search step
+pub fn vnd_with<S: Solution>(start: S, neighborhoods: &[&dyn Neighborhood<S>], pivot_rule: PivotRule, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S {
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+    let result = descend(start, neighborhoods, pivot_rule, &mut budget, observer);
+    observer.on_finished(&result);
+    result
+}
+
+/// Forwards the callbacks of the single step local search runs of a descent.
+/// Iterations are counted over the whole descent, the end is reported by the descent itself
+struct StepObserver<'a, S> {
+    inner: &'a mut dyn Observer<S>,
+    iteration: usize,
+}
+
+impl<S> Observer<S> for StepObserver<'_, S> {
+    fn on_iteration_start(&mut self, _iteration: usize, current: &S) {
+        self.inner.on_iteration_start(self.iteration, current);
+    }
+    fn on_neighbor_evaluated(&mut self, improving: bool) {
+        self.inner.on_neighbor_evaluated(improving);
+    }
+    fn on_improvement(&mut self, solution: &S) {
+        self.inner.on_improvement(solution);
+    }
+}
+
+/// One local search step in neighborhood k, back to the first neighborhood on success, otherwise next neighborhood
+fn descend<S: Solution>(start: S, neighborhoods: &[&dyn Neighborhood<S>], pivot_rule: PivotRule, budget: &mut Budget<S::Cost>, observer: &mut dyn Observer<S>) -> S {
+    let mut current_cost = start.cost();
+    let mut current = start;
+    let mut k = 0;
+    while k < neighborhoods.len() && !budget.should_stop(&current_cost) {
+        let mut step_observer = StepObserver { inner: &mut *observer, iteration: budget.iterations };
+        let mut run = LocalSearchRun::with_cost(current, current_cost, neighborhoods[k])
+            .with_pivot_rule(pivot_rule)
+            .with_termination(&budget.remaining())
+            .with_observer(&mut step_observer);
+        let outcome = run.step();
+        budget.count_iteration();
+        budget.add_evaluations(run.budget().evaluations);
+        current_cost = run.current_cost();
+        current = run.into_solution();
+        match outcome {
+            StepOutcome::Changed => k = 0,
+            StepOutcome::Finished => k += 1,
+            StepOutcome::Stopped => break,
+        }
+    }
+    current
+}
+
+/// General variable neighborhood search, shakes in neighborhood k and descends with VND
+pub fn solve<S: Solution>(start: S, neighborhoods: &[&dyn Neighborhood<S>], config: &VnsConfig) -> S {
+    solve_with(start, neighborhoods, config, &Termination::default(), None)
+}
+
+/// General variable neighborhood search with termination, iterations of the termination count descent steps
+pub fn solve_with<S: Solution>(start: S, neighborhoods: &[&dyn Neighborhood<S>], config: &VnsConfig, termination: &Termination<S::Cost>, observer: Option<&mut dyn Observer<S>>) -> S {
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    let mut budget = termination.start();
+
+    // Descent runs silently, only improvements of the best solution are reported
+    let mut current = descend(start, neighborhoods, config.pivot_rule, &mut budget, &mut NoObserver);
+    let mut current_cost = current.cost();
+
+    'outer: for _ in 0..config.max_iterations {
+        observer.on_iteration_start(budget.iterations, &current);
+        let mut k = 0;
+        while k < neighborhoods.len() {
+            if budget.should_stop(&current_cost) {
+                break 'outer;
+            }
+            // Shaking
+            let mut shaken = current.clone();
+            for _ in 0..config.shake_strength {
+                match neighborhoods[k].random_neighbor(&shaken) {
+                    Some(neighbor) => shaken = neighbor,
+                    None => break,
+                }
+            }
+            let candidate = descend(shaken, neighborhoods, config.pivot_rule, &mut budget, &mut NoObserver);
+            let candidate_cost = candidate.cost();
+            let improving = candidate_cost < current_cost;
+            observer.on_neighbor_evaluated(improving);
+            // Move or next neighborhood
+            if improving {
+                current = candidate;
+                current_cost = candidate_cost;
+                observer.on_improvement(&current);
+                k = 0;
+            } else {
+                k += 1;
+            }
+        }
+    }
+    observer.on_finished(&current);
+    current
+}
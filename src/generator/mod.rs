@@ -1,31 +1,64 @@
 use rand::Rng;
-use crate::problem::instance::Instance;
-use crate::problem::rect::Rect;
+use crate::problem::instance::{BoxType, Instance};
+use crate::problem::rect::{Rect, RectType, Rotation};
 
 pub struct Generator;
 
 impl Generator {
-    pub fn generate_instance(num_rects: usize, width_range: (u32, u32), height_range: (u32, u32), box_size: u32) -> Instance {
+    pub fn generate_instance(num_rects: usize, width_range: (u32, u32), height_range: (u32, u32), box_width: u32, box_height: u32) -> Instance {
+        Self::generate_mixed_rotation(num_rects, width_range, height_range, box_width, box_height, 0.0, 0.0)
+    }
+
+    /// Instance where about fixed_share of the rects must not be rotated and rotated_share
+    /// must be rotated, the others are free
+    pub fn generate_mixed_rotation(num_rects: usize, width_range: (u32, u32), height_range: (u32, u32), box_width: u32, box_height: u32,
+                                   fixed_share: f64, rotated_share: f64) -> Instance {
         let mut rng = rand::rng();
         let mut rects = Vec::with_capacity(num_rects);
+        let box_type = BoxType::new(box_width, box_height, 1);
 
         let (min_w, max_w) = width_range;
         let (min_h, max_h) = height_range;
         // Test values
         assert!(min_w <= max_w, "Min width must be <= max width");
         assert!(min_h <= max_h, "Min height must be <= max height");
+        assert!(fixed_share >= 0.0 && rotated_share >= 0.0 && fixed_share + rotated_share <= 1.0,
+            "Shares of fixed and rotated rectangles must be in [0, 1]");
 
         for i in 0..num_rects {
             // Random width and height in interval inclusive both borders
             let width = rng.random_range(min_w..=max_w);
             let height = rng.random_range(min_h..=max_h);
-            // Check box limit L
-            assert!(width <= box_size && height <= box_size, 
-                "Generated rectangle ({}, {}) doesn't fit in box ({})", width, height, box_size);
-            
-            rects.push(Rect::new(i, width, height));
+            let share = rng.random::<f64>();
+            let rotation = if share < fixed_share {
+                Rotation::Fixed
+            } else if share < fixed_share + rotated_share {
+                Rotation::Rotated
+            } else {
+                Rotation::Free
+            };
+            // Check box limits W x H in one of the allowed orientations
+            let rect = Rect::new(i, width, height).with_rotation(rotation);
+            assert!(rect.orientations().iter().any(|&rotated| box_type.fits(rect, rotated)),
+                "Generated rectangle ({}, {}) with rotation {:?} doesn't fit in box ({}, {})", width, height, rotation, box_width, box_height);
+
+            rects.push(rect);
         }
         
-        Instance::new(box_size, rects)
+        Instance::new(box_width, box_height, rects)
+    }
+
+    /// Instance of num_types rect types with a random demand each, every piece is a separate rect
+    pub fn generate_with_demands(num_types: usize, demand_range: (usize, usize), width_range: (u32, u32), height_range: (u32, u32),
+                                 box_width: u32, box_height: u32) -> Instance {
+        let mut rng = rand::rng();
+        let (min_d, max_d) = demand_range;
+        assert!(min_d <= max_d, "Min demand must be <= max demand");
+        // Dimensions and range checks of the types
+        let shapes = Self::generate_instance(num_types, width_range, height_range, box_width, box_height).rects;
+        let rect_types: Vec<RectType> = shapes.iter()
+            .map(|r| RectType::new(r.width, r.height, rng.random_range(min_d..=max_d)))
+            .collect();
+        Instance::from_demands(box_width, box_height, &rect_types)
     }
 }
@@ -1,6 +1,10 @@
-use optalgos_program::testing::{self, TestConfig};
+use optalgos_program::problem::instance::BoxType;
+use optalgos_program::testing::{self, BoxTypeConfig, RotationConfig, TestConfig};
 
 fn main() {
+    // Solver observers log through the log crate, enable with RUST_LOG
+    env_logger::init();
+
     println!("\n>>> Small Instances");
     let tests_demo = vec![
         TestConfig {
@@ -8,14 +12,24 @@ fn main() {
             num_rects: 30,
             width_range: (5, 20),
             height_range: (5, 20),
-            box_size: 40, 
+            box_width: 40,
+            box_height: 40, 
         },
         TestConfig {
             num_instances: 5,
             num_rects: 100,
             width_range: (10, 30),
             height_range: (10, 30),
-            box_size: 100,
+            box_width: 100,
+            box_height: 100,
+        },
+        TestConfig {
+            num_instances: 5,
+            num_rects: 30,
+            width_range: (200, 1200),
+            height_range: (100, 600),
+            box_width: 2440,
+            box_height: 1220,
         }
     ];
     testing::run_suite(&tests_demo);
@@ -27,18 +41,64 @@ fn main() {
             num_rects: 500,
             width_range: (10, 50),
             height_range: (10, 50),
-            box_size: 150,
+            box_width: 150,
+            box_height: 150,
         },
         TestConfig {
             num_instances: 1,
             num_rects: 1000,
             width_range: (10, 80),
             height_range: (10, 80),
-            box_size: 300, 
+            box_width: 300,
+            box_height: 300, 
         }
     ];
     testing::run_suite(&tests_large);
     
+    println!("\n>>> Mixed Rotation");
+    let tests_rotation = vec![
+        RotationConfig {
+            num_instances: 5,
+            num_rects: 30,
+            width_range: (5, 30),
+            height_range: (5, 20),
+            box_width: 40,
+            box_height: 30,
+            fixed_share: 0.3,
+            rotated_share: 0.2,
+        },
+        RotationConfig {
+            num_instances: 3,
+            num_rects: 100,
+            width_range: (200, 1200),
+            height_range: (100, 600),
+            box_width: 2440,
+            box_height: 1220,
+            fixed_share: 0.5,
+            rotated_share: 0.25,
+        }
+    ];
+    testing::run_rotation_suite(&tests_rotation);
+
+    println!("\n>>> Box Types");
+    let tests_box_types = vec![
+        BoxTypeConfig {
+            num_instances: 5,
+            num_rects: 30,
+            width_range: (5, 20),
+            height_range: (5, 20),
+            box_types: vec![BoxType::new(40, 40, 10), BoxType::new(40, 20, 6), BoxType::new(20, 20, 3)],
+        },
+        BoxTypeConfig {
+            num_instances: 3,
+            num_rects: 60,
+            width_range: (200, 1200),
+            height_range: (100, 600),
+            box_types: vec![BoxType::new(2440, 1220, 20), BoxType::new(1220, 1220, 11), BoxType::new(2440, 610, 12)],
+        }
+    ];
+    testing::run_box_type_suite(&tests_box_types);
+
     println!("\n=== Tests completed! ===");
 }
 
@@ -0,0 +1,158 @@
+use crate::algorithms::alns::{Destroy, Repair};
+use super::rect::Rect;
+use super::solution::{RectangleSolution, Placement};
+use rand::{Rng, rng};
+use rand::seq::index::sample;
+
+/// Rectangle solution with removed rectangles
+pub struct RemovedRects {
+    pub solution: RectangleSolution,
+    pub removed: Vec<Rect>,
+}
+
+/// Remove the given placements (box index, placement index) and all boxes which become empty
+fn remove_placements(solution: &RectangleSolution, mut selected: Vec<(usize, usize)>) -> RemovedRects {
+    let mut new_sol = solution.clone();
+    // Remove from back to front, so indices stay valid
+    selected.sort_unstable_by(|a, b| b.cmp(a));
+    let removed = selected.into_iter()
+        .map(|(box_idx, p_idx)| new_sol.boxes[box_idx].remove(p_idx).rect)
+        .collect();
+    new_sol.boxes.retain(|b| !b.placements().is_empty());
+    RemovedRects { solution: new_sol, removed }
+}
+
+/// All placements as (box index, placement index)
+fn all_placements(solution: &RectangleSolution) -> Vec<(usize, usize)> {
+    solution.boxes.iter().enumerate()
+        .flat_map(|(box_idx, b)| (0..b.placements().len()).map(move |p_idx| (box_idx, p_idx)))
+        .collect()
+}
+
+// ---------------------------------------------------------
+// Destroy operators
+// ---------------------------------------------------------
+
+/// Remove k random rectangles
+pub struct RandomRemoval {
+    pub k: usize,
+}
+
+impl Destroy<RectangleSolution, RemovedRects> for RandomRemoval {
+    fn destroy(&self, solution: &RectangleSolution) -> RemovedRects {
+        let placements = all_placements(solution);
+        let k = self.k.min(placements.len());
+        let selected = sample(&mut rng(), placements.len(), k).into_iter().map(|i| placements[i]).collect();
+        remove_placements(solution, selected)
+    }
+}
+
+/// Remove all rectangles of the boxes with least used area
+pub struct WorstBoxRemoval {
+    pub boxes: usize,
+}
+
+impl Destroy<RectangleSolution, RemovedRects> for WorstBoxRemoval {
+    fn destroy(&self, solution: &RectangleSolution) -> RemovedRects {
+        let mut order: Vec<usize> = (0..solution.boxes.len()).collect();
+        order.sort_by_key(|&i| solution.boxes[i].used_area());
+        let selected = order.into_iter().take(self.boxes)
+            .flat_map(|box_idx| (0..solution.boxes[box_idx].placements().len()).map(move |p_idx| (box_idx, p_idx)))
+            .collect();
+        remove_placements(solution, selected)
+    }
+}
+
+/// Remove a random rectangle and the k - 1 rectangles most similar in size
+pub struct RelatedSizeRemoval {
+    pub k: usize,
+}
+
+impl Destroy<RectangleSolution, RemovedRects> for RelatedSizeRemoval {
+    fn destroy(&self, solution: &RectangleSolution) -> RemovedRects {
+        let mut placements = all_placements(solution);
+        if placements.is_empty() {
+            return remove_placements(solution, placements);
+        }
+        let rect_of = |(b, p): (usize, usize)| solution.boxes[b].placements()[p].rect;
+        let seed = rect_of(placements[rng().random_range(0..placements.len())]);
+        // Size difference independent of orientation
+        let relatedness = |r: Rect| {
+            let straight = r.width.abs_diff(seed.width) + r.height.abs_diff(seed.height);
+            let rotated = r.width.abs_diff(seed.height) + r.height.abs_diff(seed.width);
+            straight.min(rotated)
+        };
+        placements.sort_by_key(|&pos| relatedness(rect_of(pos)));
+        placements.truncate(self.k);
+        remove_placements(solution, placements)
+    }
+}
+
+/// Remove k rectangles close to a random rectangle in the same box
+pub struct SpatialClusterRemoval {
+    pub k: usize,
+}
+
+impl Destroy<RectangleSolution, RemovedRects> for SpatialClusterRemoval {
+    fn destroy(&self, solution: &RectangleSolution) -> RemovedRects {
+        if solution.boxes.is_empty() {
+            return remove_placements(solution, Vec::new());
+        }
+        let mut rng = rng();
+        let box_idx = rng.random_range(0..solution.boxes.len());
+        let placements = solution.boxes[box_idx].placements();
+        let seed = placements[rng.random_range(0..placements.len())];
+        // Squared distance of the doubled centers, avoids fractions
+        let center = |p: &Placement| ((2 * p.x + p.width()) as i64, (2 * p.y + p.height()) as i64);
+        let (sx, sy) = center(&seed);
+        let mut order: Vec<usize> = (0..placements.len()).collect();
+        order.sort_by_key(|&i| {
+            let (x, y) = center(&placements[i]);
+            (x - sx).pow(2) + (y - sy).pow(2)
+        });
+        let selected = order.into_iter().take(self.k).map(|p_idx| (box_idx, p_idx)).collect();
+        remove_placements(solution, selected)
+    }
+}
+
+// ---------------------------------------------------------
+// Repair operators
+// ---------------------------------------------------------
+
+/// Insert largest rectangles first into the first box with a position of the placement rule
+pub struct FirstFitRepair;
+
+impl Repair<RectangleSolution, RemovedRects> for FirstFitRepair {
+    fn repair(&self, partial: RemovedRects) -> RectangleSolution {
+        let RemovedRects { mut solution, mut removed } = partial;
+        removed.sort_by_key(|r| std::cmp::Reverse(r.area()));
+        for rect in removed {
+            let target = (0..solution.boxes.len()).find_map(|i| solution.find_position(i, rect).map(|pos| (i, pos)));
+            match target {
+                Some((i, (x, y, rotated))) => solution.boxes[i].place(Placement { rect, x, y, rotated }),
+                None => solution.open_box(rect),
+            }
+        }
+        solution
+    }
+}
+
+/// Insert largest rectangles first into the fullest box with a position of the placement rule
+pub struct FullestBoxFirstRepair;
+
+impl Repair<RectangleSolution, RemovedRects> for FullestBoxFirstRepair {
+    fn repair(&self, partial: RemovedRects) -> RectangleSolution {
+        let RemovedRects { mut solution, mut removed } = partial;
+        removed.sort_by_key(|r| std::cmp::Reverse(r.area()));
+        for rect in removed {
+            let mut order: Vec<usize> = (0..solution.boxes.len()).collect();
+            order.sort_by_key(|&i| std::cmp::Reverse(solution.boxes[i].used_area()));
+            let target = order.into_iter().find_map(|i| solution.find_position(i, rect).map(|pos| (i, pos)));
+            match target {
+                Some((i, (x, y, rotated))) => solution.boxes[i].place(Placement { rect, x, y, rotated }),
+                None => solution.open_box(rect),
+            }
+        }
+        solution
+    }
+}
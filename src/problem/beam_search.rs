@@ -0,0 +1,50 @@
+use super::greedy::RectangleGreedyState;
+
+/// Free area in all opened boxes
+pub fn waste(state: &RectangleGreedyState) -> f64 {
+    let box_types = &state.solution.instance.box_types;
+    state.solution.boxes.iter()
+        .map(|b| (box_types[b.box_type].area() - b.used_area() as u64) as f64)
+        .sum()
+}
+
+/// Negative sum of squared used area, like the second part of the solution cost
+pub fn used_area(state: &RectangleGreedyState) -> f64 {
+    -state.solution.boxes.iter()
+        .map(|b| (b.used_area() as f64).powi(2))
+        .sum::<f64>()
+}
+
+/// Opened boxes plus the boxes of the largest type needed for the remaining area if the free area
+/// could be used completely, the fraction of waste in [0, 1) breaks ties
+pub fn area_lower_bound(state: &RectangleGreedyState) -> f64 {
+    let capacity = state.solution.instance.box_area() as f64;
+    let opened = state.solution.boxes.len() as f64;
+    let free = waste(state);
+    let remaining: f64 = state.remaining_rects.iter().map(|r| r.area() as f64).sum();
+    let additional = ((remaining - free).max(0.0) / capacity).ceil();
+    let waste_fraction = if opened > 0.0 { free / (opened * capacity) } else { 0.0 };
+    opened + additional + waste_fraction * 0.999
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::beam_search::{solve_with, BeamConfig};
+    use crate::algorithms::termination::Termination;
+    use crate::algorithms::traits::GreedyState;
+    use crate::generator::Generator;
+    use crate::problem::rect::Rect;
+    use crate::problem::validation::validate;
+    use std::sync::Arc;
+
+    #[test]
+    fn stopped_beam_is_completed() {
+        let instance = Arc::new(Generator::generate_instance(20, (5, 20), (5, 20), 40, 40));
+        let initial = RectangleGreedyState::new(instance);
+        let termination = Termination::<()>::default().with_max_iterations(2);
+        let state = solve_with(&initial, |r: &Rect| r.area() as f64, area_lower_bound, &BeamConfig::default(), &termination, None);
+        assert!(state.is_finished());
+        assert_eq!(validate(&state.solution), Ok(()));
+    }
+}
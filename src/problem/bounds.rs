@@ -0,0 +1,128 @@
+use super::instance::{BoxType, Instance};
+use super::rect::Rect;
+
+/// Lower bounds for the number of boxes of an instance with a single box type, rects in their allowed orientations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LowerBounds {
+    pub l0: usize,
+    pub l1: usize,
+    pub l2: usize,
+}
+
+impl LowerBounds {
+    /// Panics if the instance has more than one box type
+    pub fn new(instance: &Instance) -> Self {
+        Self { l0: area_bound(instance), l1: l1_bound(instance), l2: l2_bound(instance) }
+    }
+
+    /// Best of all bounds
+    pub fn best(&self) -> usize {
+        self.l0.max(self.l1).max(self.l2)
+    }
+}
+
+// The only box type, the bounds don't mix box sizes
+fn box_type(instance: &Instance) -> BoxType {
+    assert_eq!(instance.box_types.len(), 1, "Lower bounds need a single box type");
+    instance.box_types[0]
+}
+
+// Shortest extent along the box width and along the box height over the allowed orientations fitting the box.
+// In a square box both are the shorter side of a freely rotatable rect
+fn min_extents(instance: &Instance, rect: &Rect) -> (u32, u32) {
+    let BoxType { width, height, .. } = box_type(instance);
+    let orientations: Vec<(u32, u32)> = rect.orientations().iter()
+        .map(|&rotated| if rotated { (rect.height, rect.width) } else { (rect.width, rect.height) })
+        .collect();
+    let fitting: Vec<(u32, u32)> = orientations.iter().copied().filter(|&(w, h)| w <= width && h <= height).collect();
+    let fitting = if fitting.is_empty() { orientations } else { fitting };
+    (fitting.iter().map(|e| e.0).min().unwrap_or(0), fitting.iter().map(|e| e.1).min().unwrap_or(0))
+}
+
+/// Continuous bound L0: total area divided by box area
+pub fn area_bound(instance: &Instance) -> usize {
+    let area: u64 = instance.rects.iter().map(|r| r.area() as u64).sum();
+    area.div_ceil(box_type(instance).area()) as usize
+}
+
+/// Large item classes for parameter p in [1, W/2] along the width and q = p * H / W along the height,
+/// with (a, b) the min extents of an item:
+/// J1 a > W - p and b > H - q, J2 a > W/2 and b > H/2, J3 a >= p and b >= q.
+/// Items of J1 and J2 are pairwise incompatible, items of J1 can't share a box with J2 or J3
+struct ItemClasses<'a> {
+    j1: usize,
+    j2: Vec<&'a Rect>,
+    j3: Vec<&'a Rect>,
+}
+
+// Parameters (p, q) of the item classes, p = q in a square box
+fn parameters(instance: &Instance) -> impl Iterator<Item = (u32, u32)> {
+    let BoxType { width, height, .. } = box_type(instance);
+    (1..=width / 2).map(move |p| (p, ((p as u64 * height as u64 / width as u64) as u32).max(1)))
+}
+
+fn classify(instance: &Instance, p: u32, q: u32) -> ItemClasses<'_> {
+    let BoxType { width, height, .. } = box_type(instance);
+    let mut classes = ItemClasses { j1: 0, j2: Vec::new(), j3: Vec::new() };
+    for rect in &instance.rects {
+        let (a, b) = min_extents(instance, rect);
+        if a > width - p && b > height - q {
+            classes.j1 += 1;
+        } else if 2 * a > width && 2 * b > height {
+            classes.j2.push(rect);
+        } else if a >= p && b >= q {
+            classes.j3.push(rect);
+        }
+    }
+    classes
+}
+
+/// Counting bound L1: J1 and J2 items need own boxes, a box holds at most (W / p) * (H / q) items of J2 and J3
+pub fn l1_bound(instance: &Instance) -> usize {
+    parameters(instance)
+        .map(|(p, q)| {
+            let classes = classify(instance, p, q);
+            let BoxType { width, height, .. } = box_type(instance);
+            let per_box = (width / p) as usize * (height / q) as usize;
+            let counted = (classes.j2.len() + classes.j3.len()).div_ceil(per_box);
+            classes.j1 + classes.j2.len().max(counted)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Martello-Vigo bound L2: J1 and J2 items need own boxes, J3 items fill the free area
+/// of the J2 boxes and the remaining area needs additional boxes. Rects larger than the box leave no free area
+pub fn l2_bound(instance: &Instance) -> usize {
+    let capacity = box_type(instance).area();
+    parameters(instance)
+        .map(|(p, q)| {
+            let classes = classify(instance, p, q);
+            let free: u64 = classes.j2.iter().map(|r| capacity.saturating_sub(r.area() as u64)).sum();
+            let area: u64 = classes.j3.iter().map(|r| r.area() as u64).sum();
+            classes.j1 + classes.j2.len() + area.saturating_sub(free).div_ceil(capacity) as usize
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::rect::Rotation;
+
+    #[test]
+    fn rect_larger_than_the_box() {
+        let rects = vec![Rect::new(0, 90, 200).with_rotation(Rotation::Fixed), Rect::new(1, 30, 30)];
+        let bounds = LowerBounds::new(&Instance::new(100, 100, rects));
+        assert_eq!(bounds.l2, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "single box type")]
+    fn several_box_types() {
+        let instance = Instance::new(100, 100, vec![Rect::new(0, 10, 10)])
+            .with_box_types(vec![BoxType::new(100, 100, 1), BoxType::new(50, 50, 1)]);
+        LowerBounds::new(&instance);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::algorithms::greedy;
+use crate::algorithms::observer::{NoObserver, Observer};
+use crate::algorithms::termination::{Budget, Termination};
+use super::bounds::LowerBounds;
+use super::greedy::{RectangleGreedyState, SortByAreaStrategy};
+use super::instance::Instance;
+use super::rect::Rect;
+use super::solution::{find_position_in, BoxBin, PlacementRule, RectangleSolution};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+type Cost = (usize, i64);
+
+/// Result of the exact solver
+#[derive(Clone, Debug)]
+pub struct ExactSolution {
+    pub solution: RectangleSolution,
+    // True if the solution has the minimal number of boxes. The search over bottom-left positions is only
+    // complete without guillotine mode, guillotine solutions are optimal if they reach the lower bound
+    pub optimal: bool,
+    pub lower_bound: usize,
+}
+
+/// Branch and bound for small instances with a single box type, returns best solution found within the time limit
+pub fn solve(instance: Arc<Instance>, time_limit: Duration) -> ExactSolution {
+    solve_with(instance, &Termination::default().with_max_duration(time_limit), None)
+}
+
+/// Branch and bound with termination. Nodes are counted as iterations,
+/// positions tried by the placement search as evaluations
+pub fn solve_with(instance: Arc<Instance>, termination: &Termination<Cost>, observer: Option<&mut dyn Observer<RectangleSolution>>) -> ExactSolution {
+    let mut no_observer = NoObserver;
+    let observer = observer.unwrap_or(&mut no_observer);
+    assert_eq!(instance.box_types.len(), 1, "Branch and bound needs a single box type");
+
+    // Greedy solution as first upper bound
+    let mut state = RectangleGreedyState::new(Arc::clone(&instance));
+    greedy::solve(&mut state, &mut SortByAreaStrategy);
+    let lower_bound = LowerBounds::new(&instance).best();
+
+    // Large rectangles first, they have the fewest feasible boxes
+    let mut rects = instance.rects.clone();
+    rects.sort_by_key(|r| std::cmp::Reverse(r.area()));
+    let mut remaining_area = vec![0; rects.len() + 1];
+    for i in (0..rects.len()).rev() {
+        remaining_area[i] = remaining_area[i + 1] + rects[i].area() as u64;
+    }
+
+    let mut search = Search {
+        rects,
+        remaining_area,
+        boxes: Vec::new(),
+        best: state.solution.boxes,
+        lower_bound,
+        budget: termination.start(),
+        stopped: false,
+        packings: HashMap::new(),
+        instance: Arc::clone(&instance),
+        observer,
+    };
+    search.branch(0);
+
+    let optimal = !search.stopped && (!instance.guillotine || search.best.len() <= lower_bound);
+    let mut solution = RectangleSolution::new(instance);
+    solution.set_boxes(search.best);
+    search.observer.on_finished(&solution);
+    ExactSolution { solution, optimal, lower_bound }
+}
+
+struct Search<'a> {
+    // Rectangles in branching order and the area of rects[i..]
+    rects: Vec<Rect>,
+    remaining_area: Vec<u64>,
+    boxes: Vec<BoxBin>,
+    best: Vec<BoxBin>,
+    lower_bound: usize,
+    budget: Budget<Cost>,
+    stopped: bool,
+    // Exact packings of rect sets (sorted ids), None if the set doesn't fit
+    packings: HashMap<Vec<usize>, Option<BoxBin>>,
+    instance: Arc<Instance>,
+    observer: &'a mut dyn Observer<RectangleSolution>,
+}
+
+impl Search<'_> {
+    /// Assign rects[idx] to every open box and to a new box
+    fn branch(&mut self, idx: usize) {
+        if self.stopped || self.best.len() <= self.lower_bound {
+            return;
+        }
+        if self.budget.is_exhausted() {
+            self.stopped = true;
+            return;
+        }
+        self.budget.count_iteration();
+
+        if idx == self.rects.len() {
+            if self.boxes.len() < self.best.len() {
+                self.best = self.boxes.clone();
+                let mut solution = RectangleSolution::new(Arc::clone(&self.instance));
+                solution.set_boxes(self.best.clone());
+                self.observer.on_improvement(&solution);
+            }
+            return;
+        }
+        // Area bound, the free area of open boxes may be used completely
+        let box_area = self.instance.box_types[0].area();
+        let free: u64 = self.boxes.iter().map(|b| box_area - b.used_area() as u64).sum();
+        let needed = self.boxes.len() + self.remaining_area[idx].saturating_sub(free).div_ceil(box_area) as usize;
+        if needed >= self.best.len() {
+            return;
+        }
+
+        let rect = self.rects[idx];
+        for box_idx in 0..self.boxes.len() {
+            if self.boxes[box_idx].used_area() as u64 + rect.area() as u64 > box_area {
+                continue;
+            }
+            if let Some(bin) = self.insert(box_idx, rect) {
+                let old = std::mem::replace(&mut self.boxes[box_idx], bin);
+                self.branch(idx + 1);
+                self.boxes[box_idx] = old;
+            }
+            if self.stopped {
+                return;
+            }
+        }
+        // Open boxes are interchangeable, so a new box is only opened at the end
+        if self.boxes.len() + 1 < self.best.len() {
+            let mut bin = self.instance.new_box(0);
+            rect.orientations().iter().any(|&rotated| bin.try_place(rect, 0, 0, rotated));
+            self.boxes.push(bin);
+            self.branch(idx + 1);
+            self.boxes.pop();
+        }
+    }
+
+    /// Box with rect added, None if rect doesn't fit
+    fn insert(&mut self, box_idx: usize, rect: Rect) -> Option<BoxBin> {
+        let bin = &self.boxes[box_idx];
+        // Candidate points often suffice
+        if let Some((x, y, rotated)) = find_position_in(&self.instance, bin, rect, rect.orientations(), PlacementRule::default()) {
+            let mut new_bin = bin.clone();
+            new_bin.try_place(rect, x, y, rotated);
+            return Some(new_bin);
+        }
+        let mut set: Vec<Rect> = bin.placements().iter().map(|p| p.rect).chain(std::iter::once(rect)).collect();
+        set.sort_by_key(|r| r.id);
+        let key: Vec<usize> = set.iter().map(|r| r.id).collect();
+        if let Some(packing) = self.packings.get(&key) {
+            return packing.clone();
+        }
+        let mut new_bin = self.instance.new_box(0);
+        let packed = pack_rects(&mut new_bin, &mut set, self.instance.guillotine, &mut self.budget);
+        if !packed && self.budget.is_exhausted() {
+            // Unknown, not cached
+            self.stopped = true;
+            return None;
+        }
+        let packing = packed.then_some(new_bin);
+        self.packings.insert(key, packing.clone());
+        packing
+    }
+}
+
+/// Exact placement search: place any remaining rect at a bottom-left position (left or bottom border,
+/// right or top edge of a placed rect) in both orientations. Complete, because every packing can be
+/// pushed left and down until each rect touches the border or a rect placed before it.
+/// In guillotine mode packings without edge to edge cuts are pruned, more rects never make them cuttable
+fn pack_rects(bin: &mut BoxBin, remaining: &mut Vec<Rect>, guillotine: bool, budget: &mut Budget<Cost>) -> bool {
+    if remaining.is_empty() {
+        return true;
+    }
+    if budget.is_exhausted() {
+        return false;
+    }
+    let mut xs: Vec<u32> = bin.placements().iter().map(|p| p.x + p.width()).chain(std::iter::once(0)).collect();
+    let mut ys: Vec<u32> = bin.placements().iter().map(|p| p.y + p.height()).chain(std::iter::once(0)).collect();
+    xs.sort_unstable();
+    xs.dedup();
+    ys.sort_unstable();
+    ys.dedup();
+
+    for i in 0..remaining.len() {
+        let rect = remaining[i];
+        // Identical rects are interchangeable
+        if remaining[..i].iter().any(|r| (r.width, r.height, r.rotation) == (rect.width, rect.height, rect.rotation)) {
+            continue;
+        }
+        let orientations = if rect.width == rect.height { &rect.orientations()[..1] } else { rect.orientations() };
+        remaining.swap_remove(i);
+        for &y in &ys {
+            for &x in &xs {
+                for &rotated in orientations {
+                    budget.count_evaluation();
+                    if bin.try_place(rect, x, y, rotated) {
+                        if (!guillotine || bin.is_guillotine()) && pack_rects(bin, remaining, guillotine, budget) {
+                            return true;
+                        }
+                        bin.pop();
+                    }
+                }
+            }
+        }
+        // Restore order of remaining rects
+        remaining.push(rect);
+        let last = remaining.len() - 1;
+        remaining.swap(i, last);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::validation::validate;
+
+    // Pinwheel of four 3x2 rects around a 1x1 rect, fills a 5x5 box completely and has no edge to edge cut
+    fn pinwheel(guillotine: bool) -> Arc<Instance> {
+        let rects = [(3, 2), (2, 3), (3, 2), (2, 3), (1, 1)].iter().enumerate()
+            .map(|(id, &(w, h))| Rect::new(id, w, h))
+            .collect();
+        Arc::new(Instance::new(5, 5, rects).with_guillotine(guillotine))
+    }
+
+    #[test]
+    fn pinwheel_fits_in_one_box() {
+        let exact = solve(pinwheel(false), Duration::from_secs(10));
+        assert_eq!(exact.solution.boxes.len(), 1);
+        assert!(exact.optimal);
+        assert_eq!(validate(&exact.solution), Ok(()));
+    }
+
+    #[test]
+    fn guillotine_mode_needs_two_boxes() {
+        let exact = solve(pinwheel(true), Duration::from_secs(10));
+        assert_eq!(exact.solution.boxes.len(), 2);
+        assert_eq!(exact.lower_bound, 1);
+        assert!(!exact.optimal);
+        assert_eq!(validate(&exact.solution), Ok(()));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::algorithms::brkga::RandomKeySolution;
+use crate::algorithms::traits::Solution;
+use super::instance::Instance;
+use super::solution::{first_fit, packing_cost, BoxBin, BoxSelection, PlacementRule, RectangleSolution};
+use rand::{Rng, rng};
+use std::sync::Arc;
+
+/// Random key encoding with rotation genes: keys[i] orders rect i,
+/// keys[n + i] >= 0.5 places rect i rotated
+#[derive(Clone, Debug)]
+pub struct RotationKeySolution {
+    pub instance: Arc<Instance>,
+    pub keys: Vec<f64>,
+    // Position search and box type selection of the decoder
+    pub placement: PlacementRule,
+    pub box_selection: BoxSelection,
+}
+
+impl RotationKeySolution {
+    // Random keys
+    pub fn new(instance: Arc<Instance>) -> Self {
+        let mut rng = rng();
+        let keys = (0..2 * instance.rects.len()).map(|_| rng.random::<f64>()).collect();
+        Self { instance, keys, placement: PlacementRule::default(), box_selection: BoxSelection::default() }
+    }
+
+    pub fn with_placement(mut self, rule: PlacementRule) -> Self {
+        self.placement = rule;
+        self
+    }
+
+    pub fn with_box_selection(mut self, selection: BoxSelection) -> Self {
+        self.box_selection = selection;
+        self
+    }
+
+    /// Decode keys into boxes with the first fit placement of PermutationSolution
+    pub fn decode(&self) -> Vec<BoxBin> {
+        let rects = &self.instance.rects;
+        let n = rects.len();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| self.keys[a].total_cmp(&self.keys[b]));
+        first_fit(&self.instance, self.placement, self.box_selection, order.into_iter().map(|i| (rects[i], Some(self.keys[n + i] >= 0.5))))
+    }
+
+    pub fn to_rectangle_solution(&self) -> RectangleSolution {
+        let mut solution = RectangleSolution::new(Arc::clone(&self.instance))
+            .with_placement(self.placement)
+            .with_box_selection(self.box_selection);
+        solution.set_boxes(self.decode());
+        solution
+    }
+}
+
+impl Solution for RotationKeySolution {
+    type Cost = (usize, i64);
+
+    fn cost(&self) -> Self::Cost {
+        packing_cost(&self.instance, &self.decode())
+    }
+}
+
+impl RandomKeySolution for RotationKeySolution {
+    fn keys(&self) -> &[f64] {
+        &self.keys
+    }
+
+    fn with_keys(&self, keys: Vec<f64>) -> Self {
+        Self { instance: Arc::clone(&self.instance), keys, placement: self.placement, box_selection: self.box_selection }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::algorithms::termination::{Budget, Termination};
+use super::bounds::LowerBounds;
+use super::instance::Instance;
+use super::rect::{Rect, Rotation};
+use super::solution::{first_fit, BoxBin, BoxSelection, PlacementRule};
+use std::time::Duration;
+
+/// Answer of the single box oracle
+#[derive(Clone, Debug)]
+pub enum Feasibility {
+    // All rects placed in one box
+    Feasible(BoxBin),
+    Infeasible(Infeasibility),
+    // Time budget exhausted before the search was complete
+    Unknown,
+}
+
+/// Proof why rects don't fit in one box
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Infeasibility {
+    // Rect with this id is larger than the box in all allowed orientations
+    Oversized(usize),
+    // Total area exceeds box area
+    Area,
+    // Lower bound for the number of boxes is greater than one
+    LowerBound(usize),
+    // Complete search found no packing
+    Exhausted,
+}
+
+impl Feasibility {
+    pub fn is_feasible(&self) -> bool {
+        matches!(self, Feasibility::Feasible(_))
+    }
+}
+
+/// Exact check if rects fit in one box of width x height in their allowed orientations
+pub fn fits_in_box(rects: &[Rect], width: u32, height: u32, time_limit: Duration) -> Feasibility {
+    let mut budget = Termination::<()>::default().with_max_duration(time_limit).start();
+    fits_in_box_with(rects, width, height, &mut budget)
+}
+
+/// Exact check sharing the budget of a solver, every tried position counts as evaluation
+pub fn fits_in_box_with<C>(rects: &[Rect], width: u32, height: u32, budget: &mut Budget<C>) -> Feasibility {
+    let instance = Instance::new(width, height, rects.to_vec());
+    // Bounding checks
+    let box_type = instance.box_types[0];
+    if let Some(rect) = rects.iter().find(|r| !r.orientations().iter().any(|&rotated| box_type.fits(**r, rotated))) {
+        return Feasibility::Infeasible(Infeasibility::Oversized(rect.id));
+    }
+    let area: u64 = rects.iter().map(|r| r.area() as u64).sum();
+    if area > instance.box_area() {
+        return Feasibility::Infeasible(Infeasibility::Area);
+    }
+    let bound = LowerBounds::new(&instance).best();
+    if bound > 1 {
+        return Feasibility::Infeasible(Infeasibility::LowerBound(bound));
+    }
+
+    // Large rects first, identical rects next to each other
+    let mut sorted = rects.to_vec();
+    sorted.sort_by_key(|r| (std::cmp::Reverse(r.area()), shape(r)));
+
+    // Candidate points often find a packing quickly
+    let mut boxes = first_fit(&instance, PlacementRule::default(), BoxSelection::default(), sorted.iter().map(|&r| (r, None)));
+    if boxes.len() <= 1 {
+        return Feasibility::Feasible(boxes.pop().unwrap_or_else(|| instance.new_box(0)));
+    }
+
+    let x_patterns = (0..sorted.len()).map(|i| normal_patterns(&sorted, i, width, true)).collect();
+    let y_patterns = (0..sorted.len()).map(|i| normal_patterns(&sorted, i, height, false)).collect();
+    let mut search = OracleSearch { rects: sorted, x_patterns, y_patterns, bin: instance.new_box(0), budget };
+    if search.place(0) {
+        Feasibility::Feasible(search.bin)
+    } else if search.budget.is_exhausted() {
+        Feasibility::Unknown
+    } else {
+        Feasibility::Infeasible(Infeasibility::Exhausted)
+    }
+}
+
+// Rects with equal shape are interchangeable: free rects with equal sides,
+// otherwise equal width and height in their only orientation
+fn shape(rect: &Rect) -> (u32, u32, bool) {
+    match rect.rotation {
+        Rotation::Free => (rect.width.min(rect.height), rect.width.max(rect.height), true),
+        Rotation::Fixed => (rect.width, rect.height, false),
+        Rotation::Rotated => (rect.height, rect.width, false),
+    }
+}
+
+// Extents of the allowed orientations along the x axis (horizontal) or the y axis
+fn extents(rect: &Rect, horizontal: bool) -> impl Iterator<Item = u32> + '_ {
+    rect.orientations().iter().map(move |&rotated| if rotated == horizontal { rect.height } else { rect.width })
+}
+
+/// Normal patterns of rect i: all sums of extents of the other rects up to the box side.
+/// Every packing can be pushed left and down until all coordinates are normal patterns
+fn normal_patterns(rects: &[Rect], i: usize, side: u32, horizontal: bool) -> Vec<u32> {
+    let mut reachable = vec![false; side as usize + 1];
+    reachable[0] = true;
+    for (j, rect) in rects.iter().enumerate() {
+        if j == i {
+            continue;
+        }
+        for sum in (0..=side as usize).rev() {
+            if !reachable[sum] {
+                for extent in extents(rect, horizontal) {
+                    let prev = sum.checked_sub(extent as usize);
+                    if prev.is_some_and(|p| reachable[p]) {
+                        reachable[sum] = true;
+                    }
+                }
+            }
+        }
+    }
+    (0..=side).filter(|&sum| reachable[sum as usize]).collect()
+}
+
+/// Backtracking over normal patterns, rects are placed in fixed order
+struct OracleSearch<'a, C> {
+    rects: Vec<Rect>,
+    // Normal patterns per rect for x and y coordinates
+    x_patterns: Vec<Vec<u32>>,
+    y_patterns: Vec<Vec<u32>>,
+    bin: BoxBin,
+    budget: &'a mut Budget<C>,
+}
+
+impl<C> OracleSearch<'_, C> {
+    fn place(&mut self, idx: usize) -> bool {
+        if idx == self.rects.len() {
+            return true;
+        }
+        if self.budget.is_exhausted() {
+            return false;
+        }
+        let (width, height) = (self.bin.width, self.bin.height);
+        let rect = self.rects[idx];
+        // Symmetry breaking: identical rects are placed in increasing (y, x) order
+        let previous = (idx > 0 && shape(&self.rects[idx - 1]) == shape(&rect))
+            .then(|| self.bin.placements()[idx - 1])
+            .map(|p| (p.y, p.x));
+        // The first rect is mirrored into the lower half, and into the left half if it is unique
+        let unique_first = idx == 0 && self.rects.get(1).is_none_or(|r| shape(r) != shape(&rect));
+
+        let orientations = if rect.width == rect.height { &rect.orientations()[..1] } else { rect.orientations() };
+        for &rotated in orientations {
+            let (w, h) = if rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
+            if w > width || h > height {
+                continue;
+            }
+            let max_y = if idx == 0 { (height - h) / 2 } else { height - h };
+            let max_x = if unique_first { (width - w) / 2 } else { width - w };
+            for yi in 0..self.y_patterns[idx].len() {
+                let y = self.y_patterns[idx][yi];
+                if y > max_y {
+                    break;
+                }
+                for xi in 0..self.x_patterns[idx].len() {
+                    let x = self.x_patterns[idx][xi];
+                    if x > max_x {
+                        break;
+                    }
+                    if previous.is_some_and(|prev| (y, x) <= prev) {
+                        continue;
+                    }
+                    self.budget.count_evaluation();
+                    if self.bin.try_place(rect, x, y, rotated) {
+                        if self.place(idx + 1) {
+                            return true;
+                        }
+                        self.bin.pop();
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::solution::RectangleSolution;
+    use crate::problem::validation::validate;
+    use std::sync::Arc;
+
+    fn rects(sizes: &[(u32, u32)], rotation: Rotation) -> Vec<Rect> {
+        sizes.iter().enumerate().map(|(id, &(w, h))| Rect::new(id, w, h).with_rotation(rotation)).collect()
+    }
+
+    fn check(rects: &[Rect], width: u32, height: u32) -> Feasibility {
+        fits_in_box(rects, width, height, Duration::from_secs(10))
+    }
+
+    // Box of a feasible answer with all rects in a valid layout
+    fn assert_packed(answer: &Feasibility, rects: &[Rect], width: u32, height: u32) -> BoxBin {
+        let Feasibility::Feasible(bin) = answer else { panic!("Expected a packing, got {:?}", answer) };
+        let mut solution = RectangleSolution::new(Arc::new(Instance::new(width, height, rects.to_vec())));
+        solution.add_box(bin.clone());
+        assert_eq!(validate(&solution), Ok(()));
+        bin.clone()
+    }
+
+    #[test]
+    fn perfect_tiling() {
+        // Pinwheel, the only packing has no edge to edge cut
+        let pinwheel = rects(&[(2, 1), (1, 2), (2, 1), (1, 2), (1, 1)], Rotation::Fixed);
+        assert_packed(&check(&pinwheel, 3, 3), &pinwheel, 3, 3);
+
+        let squares = rects(&[(5, 5); 4], Rotation::Free);
+        assert_packed(&check(&squares, 10, 10), &squares, 10, 10);
+    }
+
+    #[test]
+    fn fits_only_with_rotation() {
+        // Two 4x2 rects stacked, the third one upright next to them
+        let free = rects(&[(4, 2); 3], Rotation::Free);
+        let bin = assert_packed(&check(&free, 6, 4), &free, 6, 4);
+        assert!(bin.placements().iter().any(|p| p.rotated));
+
+        let fixed = rects(&[(4, 2); 3], Rotation::Fixed);
+        assert!(matches!(check(&fixed, 6, 4), Feasibility::Infeasible(_)));
+    }
+
+    #[test]
+    fn infeasible_with_small_area() {
+        // Area 13 of 16, the 2x2 rect doesn't fit next to the 3x3 rect
+        let rects = rects(&[(3, 3), (2, 2)], Rotation::Free);
+        assert!(matches!(check(&rects, 4, 4), Feasibility::Infeasible(Infeasibility::Exhausted | Infeasibility::LowerBound(_))));
+    }
+
+    #[test]
+    fn bounding_checks() {
+        let upright = rects(&[(2, 10)], Rotation::Fixed);
+        assert!(matches!(check(&upright, 10, 2), Feasibility::Infeasible(Infeasibility::Oversized(0))));
+        let free = rects(&[(2, 10)], Rotation::Free);
+        assert_packed(&check(&free, 10, 2), &free, 10, 2);
+
+        let squares = rects(&[(3, 3); 3], Rotation::Free);
+        assert!(matches!(check(&squares, 5, 5), Feasibility::Infeasible(Infeasibility::Area)));
+    }
+}
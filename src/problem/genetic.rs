@@ -0,0 +1,174 @@
+use crate::algorithms::genetic::{Crossover, Mutation};
+use super::instance::Instance;
+use super::rect::Rect;
+use super::solution::PermutationSolution;
+use rand::{Rng, rng};
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Random permutations of the instance rectangles as start population
+pub fn random_population(instance: &Arc<Instance>, size: usize) -> Vec<PermutationSolution> {
+    let mut rng = rng();
+    (0..size).map(|_| {
+        let mut rects = instance.rects.clone();
+        rects.shuffle(&mut rng);
+        PermutationSolution::new(Arc::clone(instance), rects)
+    }).collect()
+}
+
+/// Random segment [i, j] of the sequence, both borders inclusive
+fn random_segment(n: usize, rng: &mut impl Rng) -> (usize, usize) {
+    let a = rng.random_range(0..n);
+    let b = rng.random_range(0..n);
+    (a.min(b), a.max(b))
+}
+
+/// Position of each rectangle id in the sequence
+fn positions(sequence: &[Rect]) -> HashMap<usize, usize> {
+    sequence.iter().enumerate().map(|(idx, r)| (r.id, idx)).collect()
+}
+
+// ---------------------------------------------------------
+// Crossovers for PermutationSolution
+// ---------------------------------------------------------
+
+/// Order crossover (OX): segment of first parent, remaining rects in order of second parent
+pub struct OrderCrossover;
+
+impl Crossover<PermutationSolution> for OrderCrossover {
+    fn crossover(&self, first: &PermutationSolution, second: &PermutationSolution) -> PermutationSolution {
+        let n = first.sequence.len();
+        if n < 2 {
+            return first.clone();
+        }
+        let (i, j) = random_segment(n, &mut rng());
+        let segment: HashSet<usize> = first.sequence[i..=j].iter().map(|r| r.id).collect();
+
+        let mut sequence = first.sequence.clone();
+        // Fill positions behind the segment, starting after the segment in second parent
+        let mut fill = (j + 1..n).chain(0..i);
+        for k in (j + 1..n).chain(0..=j) {
+            let rect = second.sequence[k];
+            if !segment.contains(&rect.id) {
+                sequence[fill.next().unwrap()] = rect;
+            }
+        }
+        PermutationSolution::new(Arc::clone(&first.instance), sequence).with_placement(first.placement).with_box_selection(first.box_selection)
+    }
+}
+
+/// Partially mapped crossover (PMX): segment of first parent, conflicts resolved by the segment mapping
+pub struct PartiallyMappedCrossover;
+
+impl Crossover<PermutationSolution> for PartiallyMappedCrossover {
+    fn crossover(&self, first: &PermutationSolution, second: &PermutationSolution) -> PermutationSolution {
+        let n = first.sequence.len();
+        if n < 2 {
+            return first.clone();
+        }
+        let (i, j) = random_segment(n, &mut rng());
+        let segment: HashSet<usize> = first.sequence[i..=j].iter().map(|r| r.id).collect();
+        let second_pos = positions(&second.sequence);
+
+        let mut sequence = second.sequence.clone();
+        sequence[i..=j].copy_from_slice(&first.sequence[i..=j]);
+        for k in i..=j {
+            let rect = second.sequence[k];
+            if segment.contains(&rect.id) {
+                continue;
+            }
+            // Follow mapping until position outside of segment
+            let mut pos = k;
+            while (i..=j).contains(&pos) {
+                pos = second_pos[&first.sequence[pos].id];
+            }
+            sequence[pos] = rect;
+        }
+        PermutationSolution::new(Arc::clone(&first.instance), sequence).with_placement(first.placement).with_box_selection(first.box_selection)
+    }
+}
+
+/// Cycle crossover (CX): every position keeps the rect of one parent, cycles alternate between parents
+pub struct CycleCrossover;
+
+impl Crossover<PermutationSolution> for CycleCrossover {
+    fn crossover(&self, first: &PermutationSolution, second: &PermutationSolution) -> PermutationSolution {
+        let n = first.sequence.len();
+        let first_pos = positions(&first.sequence);
+        let mut sequence = second.sequence.clone();
+        let mut visited = vec![false; n];
+        let mut from_first = true;
+
+        for start in 0..n {
+            if visited[start] {
+                continue;
+            }
+            let mut pos = start;
+            while !visited[pos] {
+                visited[pos] = true;
+                if from_first {
+                    sequence[pos] = first.sequence[pos];
+                }
+                pos = first_pos[&second.sequence[pos].id];
+            }
+            from_first = !from_first;
+        }
+        PermutationSolution::new(Arc::clone(&first.instance), sequence).with_placement(first.placement).with_box_selection(first.box_selection)
+    }
+}
+
+// ---------------------------------------------------------
+// Mutations for PermutationSolution
+// ---------------------------------------------------------
+
+/// Swap two random positions
+pub struct SwapMutation;
+
+impl Mutation<PermutationSolution> for SwapMutation {
+    fn mutate(&self, solution: &mut PermutationSolution) {
+        let n = solution.sequence.len();
+        if n < 2 {
+            return;
+        }
+        let mut rng = rng();
+        let i = rng.random_range(0..n);
+        let j = rng.random_range(0..n);
+        solution.sequence.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+
+    // Crossover of random parents contains every rect id exactly once
+    fn check_permutations(crossover: &impl Crossover<PermutationSolution>) {
+        let instance = Arc::new(Generator::generate_instance(12, (2, 10), (2, 10), 20, 20));
+        let mut expected: Vec<usize> = instance.rects.iter().map(|r| r.id).collect();
+        expected.sort_unstable();
+        for _ in 0..200 {
+            let parents = random_population(&instance, 2);
+            let child = crossover.crossover(&parents[0], &parents[1]);
+            let mut ids: Vec<usize> = child.sequence.iter().map(|r| r.id).collect();
+            ids.sort_unstable();
+            assert_eq!(ids, expected);
+        }
+    }
+
+    #[test]
+    fn order_crossover_returns_permutation() {
+        check_permutations(&OrderCrossover);
+    }
+
+    #[test]
+    fn partially_mapped_crossover_returns_permutation() {
+        check_permutations(&PartiallyMappedCrossover);
+    }
+
+    #[test]
+    fn cycle_crossover_returns_permutation() {
+        check_permutations(&CycleCrossover);
+    }
+}
@@ -1,8 +1,9 @@
+use crate::algorithms::grasp::CandidateList;
 use crate::algorithms::traits::{GreedyState, SelectionStrategy};
 use super::rect::Rect;
-use super::solution::{RectangleSolution, BoxBin};
+use super::solution::{BoxSelection, RectangleSolution, Placement, PlacementRule};
 use super::instance::Instance;
-use std::collections::HashSet;
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct RectangleGreedyState {
@@ -11,10 +12,22 @@ pub struct RectangleGreedyState {
 }
 
 impl RectangleGreedyState {
-    pub fn new(instance: Instance) -> Self {
+    pub fn new(instance: Arc<Instance>) -> Self {
         let rects = instance.rects.clone();
         Self { solution: RectangleSolution::new(instance), remaining_rects: rects }
     }
+
+    // Search positions with another placement rule
+    pub fn with_placement(mut self, rule: PlacementRule) -> Self {
+        self.solution.placement = rule;
+        self
+    }
+
+    // Open boxes with another box type selection
+    pub fn with_box_selection(mut self, selection: BoxSelection) -> Self {
+        self.solution.box_selection = selection;
+        self
+    }
 }
 
 impl GreedyState for RectangleGreedyState {
@@ -30,60 +43,28 @@ impl GreedyState for RectangleGreedyState {
             self.remaining_rects.remove(pos);
         }
         // Try to place in existing boxes
-        for bin in self.solution.boxes.iter_mut() {
-            if try_place_cp(bin, rect) {
+        for idx in 0..self.solution.boxes.len() {
+            if let Some((x, y, rotated)) = self.solution.find_position(idx, rect) {
+                self.solution.boxes[idx].place(Placement { rect, x, y, rotated });
                 return;
             }
         }
-        // If no box found, open new box
-        let mut new_bin = BoxBin::new(self.solution.instance.box_size);
-        // Place it left-bottom
-        let placed = new_bin.try_place(rect, 0, 0, false);
-        if !placed { panic!("Could'nt place rectangle in new box.")}
-
-        self.solution.boxes.push(new_bin);
+        // If no box found, open new box and place it left-bottom
+        self.solution.open_box(rect);
     }
-}
 
-/// Place a rectangle with candidate points
-/// 
-/// # Arguments
-/// * `bin` - BoxBin
-/// * `rect` - Rectangle
-/// 
-/// # Returns
-/// true if placement succesfull
-fn try_place_cp(bin: &mut BoxBin, rect: Rect) -> bool {
-    // Create candidate list
-    let mut candidates = HashSet::new();
-    candidates.insert((0, 0));
-
-    for placement in &bin.placements {
-        // Right top corner of rect
-        let c1 = (placement.x + placement.width(), placement.y);
-        // Left top corner of rect
-        let c2 = (placement.x, placement.y + placement.height());
-
-        if c1.0 < bin.capacity && c1.1 < bin.capacity { candidates.insert(c1); }
-        if c2.0 < bin.capacity && c2.1 < bin.capacity {candidates.insert(c2); }
+    fn complete(&mut self) {
+        // One new box per remaining rectangle, no position search
+        for rect in std::mem::take(&mut self.remaining_rects) {
+            self.solution.open_box(rect);
+        }
     }
+}
 
-    // Sort candidates over y then x
-    let mut sorted_candidates: Vec<(u32, u32)> = candidates.into_iter().collect();
-    sorted_candidates.sort_by(|a, b| {
-        if a.1 != b.1 { a.1.cmp(&b.1) } else { a.0.cmp(&b.0) } 
-    });
-    // Try to place at candidate place
-    for (x, y) in sorted_candidates {
-        if bin.try_place(rect, x, y, false) {
-            return true;
-        }
-        // Try again rotated
-        if bin.try_place(rect, x, y, true) {
-            return true;
-        }
+impl CandidateList for RectangleGreedyState {
+    fn candidates(&self) -> Vec<Rect> {
+        self.remaining_rects.clone()
     }
-    false
 }
 
 // Selection strategies for greedy
@@ -0,0 +1,225 @@
+use super::maxrects::FreeRect;
+use super::rect::Rect;
+use super::solution::{BoxBin, Placement};
+use std::fmt;
+
+/// How the free space next to a placed rectangle is split into two free rectangles
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GuillotineSplit {
+    // Cut along the axis with the shorter leftover
+    #[default]
+    ShorterLeftoverAxis,
+    // Cut along the axis with the longer leftover
+    LongerLeftoverAxis,
+    // Smaller of the two free rectangles as small as possible
+    MinArea,
+    // Larger of the two free rectangles as large as possible
+    MaxArea,
+}
+
+impl fmt::Display for GuillotineSplit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuillotineSplit::ShorterLeftoverAxis => write!(f, "SLAS"),
+            GuillotineSplit::LongerLeftoverAxis => write!(f, "LLAS"),
+            GuillotineSplit::MinArea => write!(f, "MINAS"),
+            GuillotineSplit::MaxArea => write!(f, "MAXAS"),
+        }
+    }
+}
+
+impl GuillotineSplit {
+    /// True if the region is cut horizontally first, the piece right of the rect then has the rect height
+    fn horizontal(&self, region: &FreeRect, w: u32, h: u32) -> bool {
+        let (left_w, left_h) = (region.width - w, region.height - h);
+        match self {
+            GuillotineSplit::ShorterLeftoverAxis => left_w <= left_h,
+            GuillotineSplit::LongerLeftoverAxis => left_w > left_h,
+            // Horizontal cut: right piece left_w * h, top piece region.width * left_h
+            GuillotineSplit::MinArea => left_w * h < w * left_h,
+            GuillotineSplit::MaxArea => left_w * h >= w * left_h,
+        }
+    }
+}
+
+/// Box is guillotine cuttable if it can be divided by edge to edge cuts until every part holds at most one rect
+pub fn is_guillotine(bin: &BoxBin) -> bool {
+    let region = FreeRect { x: 0, y: 0, width: bin.width, height: bin.height };
+    cut(region, bin.placements().iter().collect(), &mut |_, _| {})
+}
+
+/// Cut the region recursively, leaf is called for every part with at most one placement.
+/// Returns false if a part with several placements has no edge to edge cut
+fn cut(region: FreeRect, placements: Vec<&Placement>, leaf: &mut impl FnMut(FreeRect, Option<&Placement>)) -> bool {
+    if placements.len() <= 1 {
+        leaf(region, placements.first().copied());
+        return true;
+    }
+    // Vertical cuts at right borders, horizontal cuts at top borders.
+    // No placement may cross the cut and both sides must hold placements
+    let vertical = placements.iter()
+        .map(|p| p.x + p.width())
+        .filter(|&c| placements.iter().any(|p| p.x >= c))
+        .find(|&c| placements.iter().all(|p| p.x + p.width() <= c || p.x >= c));
+    if let Some(c) = vertical {
+        let (left, right) = placements.into_iter().partition(|p| p.x < c);
+        return cut(FreeRect { width: c - region.x, ..region }, left, leaf)
+            && cut(FreeRect { x: c, width: region.x + region.width - c, ..region }, right, leaf);
+    }
+    let horizontal = placements.iter()
+        .map(|p| p.y + p.height())
+        .filter(|&c| placements.iter().any(|p| p.y >= c))
+        .find(|&c| placements.iter().all(|p| p.y + p.height() <= c || p.y >= c));
+    if let Some(c) = horizontal {
+        let (bottom, top) = placements.into_iter().partition(|p| p.y < c);
+        return cut(FreeRect { height: c - region.y, ..region }, bottom, leaf)
+            && cut(FreeRect { y: c, height: region.y + region.height - c, ..region }, top, leaf);
+    }
+    false
+}
+
+/// Free space of a guillotine cuttable box as disjoint free rectangles.
+/// Every rect placed into a free rectangle keeps the box guillotine cuttable
+#[derive(Clone, Debug)]
+pub struct GuillotineFreeRects {
+    pub split: GuillotineSplit,
+    pub free: Vec<FreeRect>,
+}
+
+impl GuillotineFreeRects {
+    /// Free rectangles of the cut parts, None if the box is not guillotine cuttable.
+    /// The split rule is applied to the parts of the cuts, the order of the placements is unknown here
+    pub fn from_bin(bin: &BoxBin, split: GuillotineSplit) -> Option<Self> {
+        let mut free = Vec::new();
+        let region = FreeRect { x: 0, y: 0, width: bin.width, height: bin.height };
+        let cuttable = cut(region, bin.placements().iter().collect(), &mut |part, placement| match placement {
+            None => free.push(part),
+            Some(p) => split_around(part, p, split, &mut free),
+        });
+        cuttable.then_some(Self { split, free })
+    }
+
+    /// Split the free rectangle holding the placement with the split rule.
+    /// Returns false if no free rectangle holds the placement, the free rectangles are unchanged then
+    pub fn place(&mut self, placement: &Placement) -> bool {
+        let (w, h) = (placement.width(), placement.height());
+        let Some(idx) = self.free.iter().position(|f| holds(f, placement.x, placement.y, w, h)) else {
+            return false;
+        };
+        let part = self.free.swap_remove(idx);
+        split_around(part, placement, self.split, &mut self.free);
+        true
+    }
+
+    /// True if a free rectangle holds the area of width w and height h at x, y
+    pub fn holds(&self, x: u32, y: u32, w: u32, h: u32) -> bool {
+        self.free.iter().any(|f| holds(f, x, y, w, h))
+    }
+
+    /// Best area fit, rect is placed in the left-bottom corner of the free rectangle
+    pub fn find_position(&self, rect: Rect, orientations: &[bool]) -> Option<(u32, u32, bool)> {
+        let mut best: Option<(u64, (u32, u32, bool))> = None;
+        for f in &self.free {
+            for &rotated in orientations {
+                let (w, h) = if rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
+                if w > f.width || h > f.height {
+                    continue;
+                }
+                let waste = (f.width as u64 * f.height as u64) - (w as u64 * h as u64);
+                if best.is_none_or(|(best_waste, _)| waste < best_waste) {
+                    best = Some((waste, (f.x, f.y, rotated)));
+                }
+            }
+        }
+        best.map(|(_, position)| position)
+    }
+
+    /// Left-bottom corners of the free rectangles sorted by y then x
+    pub fn corners(&self) -> Vec<(u32, u32)> {
+        let mut corners: Vec<(u32, u32)> = self.free.iter().map(|f| (f.x, f.y)).collect();
+        corners.sort_by_key(|&(x, y)| (y, x));
+        corners.dedup();
+        corners
+    }
+}
+
+// Area of width w and height h at x, y lies in the free rectangle
+fn holds(f: &FreeRect, x: u32, y: u32, w: u32, h: u32) -> bool {
+    x >= f.x && y >= f.y && x + w <= f.x + f.width && y + h <= f.y + f.height
+}
+
+/// Free rectangles of a part around its only placement, strips left and below the placement are cut off first
+fn split_around(part: FreeRect, p: &Placement, split: GuillotineSplit, free: &mut Vec<FreeRect>) {
+    let mut push = |rect: FreeRect| if rect.width > 0 && rect.height > 0 { free.push(rect) };
+    push(FreeRect { width: p.x - part.x, ..part });
+    push(FreeRect { x: p.x, width: part.x + part.width - p.x, height: p.y - part.y, ..part });
+    // Remaining part has the placement in its left-bottom corner
+    let rest = FreeRect { x: p.x, y: p.y, width: part.x + part.width - p.x, height: part.y + part.height - p.y };
+    let (w, h) = (p.width(), p.height());
+    if split.horizontal(&rest, w, h) {
+        push(FreeRect { x: p.x + w, width: rest.width - w, height: h, ..rest });
+        push(FreeRect { y: p.y + h, height: rest.height - h, ..rest });
+    } else {
+        push(FreeRect { x: p.x + w, width: rest.width - w, ..rest });
+        push(FreeRect { y: p.y + h, width: w, height: rest.height - h, ..rest });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Box with the rects (x, y, width, height), ids in order
+    fn bin(width: u32, height: u32, rects: &[(u32, u32, u32, u32)]) -> BoxBin {
+        let mut bin = BoxBin::new(width, height);
+        for (id, &(x, y, w, h)) in rects.iter().enumerate() {
+            bin.place(Placement { rect: Rect::new(id, w, h), x, y, rotated: false });
+        }
+        bin
+    }
+
+    // Two rects stacked right of a full height rect
+    fn guillotine_layout() -> BoxBin {
+        bin(100, 100, &[(0, 0, 50, 100), (50, 0, 50, 50), (50, 50, 50, 50)])
+    }
+
+    // Four 2x1 rects around a 1x1 rect, no edge to edge cut exists
+    fn pinwheel() -> BoxBin {
+        bin(3, 3, &[(0, 0, 2, 1), (2, 0, 1, 2), (1, 2, 2, 1), (0, 1, 1, 2), (1, 1, 1, 1)])
+    }
+
+    #[test]
+    fn guillotine_layout_is_cuttable() {
+        assert!(is_guillotine(&guillotine_layout()));
+    }
+
+    #[test]
+    fn pinwheel_is_not_cuttable() {
+        assert!(!is_guillotine(&pinwheel()));
+        assert!(GuillotineFreeRects::from_bin(&pinwheel(), GuillotineSplit::default()).is_none());
+    }
+
+    #[test]
+    fn cut_visits_every_placement_once() {
+        let layout = guillotine_layout();
+        let region = FreeRect { x: 0, y: 0, width: layout.width, height: layout.height };
+        let mut ids = Vec::new();
+        assert!(cut(region, layout.placements().iter().collect(), &mut |_, p| ids.extend(p.map(|p| p.rect.id))));
+        ids.sort_unstable();
+        assert_eq!(ids, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn placements_in_free_rects_stay_cuttable() {
+        let mut layout = bin(100, 100, &[(0, 0, 40, 70)]);
+        let mut free = GuillotineFreeRects::from_bin(&layout, GuillotineSplit::default()).unwrap();
+        for id in 1..6 {
+            let rect = Rect::new(id, 30, 25);
+            let Some((x, y, rotated)) = free.find_position(rect, rect.orientations()) else { break };
+            let placement = Placement { rect, x, y, rotated };
+            assert!(free.place(&placement));
+            layout.place(placement);
+            assert!(is_guillotine(&layout));
+        }
+    }
+}
@@ -1,13 +1,127 @@
-use super::rect::Rect;
+use super::rect::{Rect, RectType};
+use super::solution::BoxBin;
+
+/// Box type of the catalogue, every opened box of the type costs `cost`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoxType {
+    pub width: u32,
+    pub height: u32,
+    pub cost: usize,
+    // Maximum number of boxes of this type, None for unlimited
+    pub available: Option<usize>,
+}
+
+impl BoxType {
+    pub fn new(width: u32, height: u32, cost: usize) -> Self {
+        Self { width, height, cost, available: None }
+    }
+
+    // Limit the number of boxes of this type
+    pub fn with_available(mut self, available: usize) -> Self {
+        self.available = Some(available);
+        self
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// True if rect fits in an empty box of this type in the given orientation
+    pub fn fits(&self, rect: Rect, rotated: bool) -> bool {
+        let (w, h) = if rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
+        w <= self.width && h <= self.height
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Instance {
-    pub box_size: u32,
+    // Catalogue of box types, never empty. Bounds and exact solvers need a single box type
+    pub box_types: Vec<BoxType>,
     pub rects: Vec<Rect>,
+    // Boxes must be cuttable with edge to edge cuts
+    pub guillotine: bool,
 }
 
 impl Instance {
-    pub fn new(box_size: u32, rects: Vec<Rect>) -> Self {
-        Self { box_size, rects }
+    // Boxes of one type box_width x box_height with unit cost, the cost is the number of boxes
+    pub fn new(box_width: u32, box_height: u32, rects: Vec<Rect>) -> Self {
+        Self { box_types: vec![BoxType::new(box_width, box_height, 1)], rects, guillotine: false }
+    }
+
+    /// Instance with one rect per piece, ids are assigned in order of the types
+    pub fn from_demands(box_width: u32, box_height: u32, rect_types: &[RectType]) -> Self {
+        let rects = rect_types.iter()
+            .flat_map(|rect_type| std::iter::repeat_n(rect_type, rect_type.demand))
+            .enumerate()
+            .map(|(id, rect_type)| rect_type.piece(id))
+            .collect();
+        Self::new(box_width, box_height, rects)
+    }
+
+    /// Identical rects compressed to rect types with their demand, in order of first occurrence
+    pub fn rect_types(&self) -> Vec<RectType> {
+        let mut rect_types: Vec<RectType> = Vec::new();
+        for rect in &self.rects {
+            match rect_types.iter_mut().find(|t| t.piece(rect.id).is_identical(rect)) {
+                Some(rect_type) => rect_type.demand += 1,
+                None => rect_types.push(RectType::new(rect.width, rect.height, 1).with_rotation(rect.rotation)),
+            }
+        }
+        rect_types
+    }
+
+    // Enable or disable guillotine mode
+    pub fn with_guillotine(mut self, guillotine: bool) -> Self {
+        self.guillotine = guillotine;
+        self
+    }
+
+    // Replace the box types, the cost is the total cost of the opened boxes
+    pub fn with_box_types(mut self, box_types: Vec<BoxType>) -> Self {
+        assert!(!box_types.is_empty(), "Instance needs at least one box type");
+        self.box_types = box_types;
+        self
+    }
+
+    // Area of the largest box type
+    pub fn box_area(&self) -> u64 {
+        self.box_types.iter().map(BoxType::area).max().unwrap_or(0)
+    }
+
+    // Empty box of the given type
+    pub fn new_box(&self, box_type: usize) -> BoxBin {
+        let BoxType { width, height, .. } = self.box_types[box_type];
+        let mut bin = BoxBin::new(width, height);
+        bin.box_type = box_type;
+        bin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::rect::{RectType, Rotation};
+
+    #[test]
+    fn demands_round_trip() {
+        // Same dimensions with other rotation permission or swapped sides are different types
+        let rect_types = vec![
+            RectType::new(10, 5, 3),
+            RectType::new(5, 10, 2),
+            RectType::new(10, 5, 1).with_rotation(Rotation::Fixed),
+            RectType::new(7, 7, 4),
+        ];
+        let instance = Instance::from_demands(20, 20, &rect_types);
+        assert_eq!(instance.rects.len(), 10);
+        assert!(instance.rects.iter().enumerate().all(|(idx, r)| r.id == idx));
+        assert_eq!(instance.rect_types(), rect_types);
+    }
+
+    #[test]
+    fn rect_types_of_interleaved_rects() {
+        let rects = vec![Rect::new(0, 4, 3), Rect::new(1, 2, 2), Rect::new(2, 4, 3), Rect::new(3, 2, 2), Rect::new(4, 4, 3)];
+        let rect_types = Instance::new(10, 10, rects).rect_types();
+        assert_eq!(rect_types, vec![RectType::new(4, 3, 3), RectType::new(2, 2, 2)]);
+        assert_eq!(Instance::from_demands(10, 10, &rect_types).rect_types(), rect_types);
     }
 }
@@ -1,10 +1,11 @@
-use crate::algorithms::traits::{Neighborhood};
-use super::solution::{RectangleSolution, PermutationSolution, Placement, BoxBin};
+use crate::algorithms::traits::{AttributedNeighborhood, Move, MoveAttributes, MoveNeighborhood, Neighborhood, Solution};
+use super::solution::{find_position_in, first_fit_from, RectangleSolution, PermutationSolution, Placement, BoxBin};
 use super::rect::Rect;
 use rand::{Rng, rng};
+use rand::seq::SliceRandom;
 use core::panic;
-use std::collections::HashSet;
-use std::iter::once_with;
+use std::iter::{once, once_with};
+use std::rc::Rc;
 
 // ---------------------------------------------------------
 // Geometric Neighborhood
@@ -13,28 +14,37 @@ pub struct GeometricNeighborhood;
 
 impl Neighborhood<RectangleSolution> for GeometricNeighborhood {
     fn neighbors<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectangleSolution> + 'a> {
-        // Iterate over all boxes and all rectangles in it
+        Box::new(self.attributed_neighbors(solution).map(|(_, neighbor)| neighbor))
+    }
+
+    fn random_neighbor(&self, solution: &RectangleSolution) -> Option<RectangleSolution> {
+        self.random_move(solution).map(|mv| neighbor_of(solution, mv))
+    }
+
+    fn shuffled_neighbors<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectangleSolution> + 'a> {
+        Box::new(self.shuffled_moves(solution).map(move |mv| neighbor_of(solution, mv)))
+    }
+}
+
+impl AttributedNeighborhood<RectangleSolution> for GeometricNeighborhood {
+    type Attribute = RectInBox;
+
+    fn attributed_neighbors<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = (MoveAttributes<RectInBox>, RectangleSolution)> + 'a> {
+        // Iterate over all boxes and all rectangles in it, identical rectangles of a box give moves of equal cost
         let moves = solution.boxes.iter().enumerate().flat_map(move |(src_idx, src_box)| {
-            src_box.placements.iter().enumerate().flat_map(move |(p_idx, placement) | {
+            let distinct = src_box.placements().iter().enumerate().filter(|&(p_idx, _)| first_of_kind(src_box.placements(), p_idx));
+            distinct.flat_map(move |(p_idx, placement)| {
                 let rect = placement.rect;
                 // Try to move rectangle into every other box
-                solution.boxes.iter().enumerate().filter_map(move |(tgt_idx, tgt_box)| {
+                (0..solution.boxes.len()).filter_map(move |tgt_idx| {
                     if src_idx == tgt_idx {
                         return None;
                     }
                     // Check if rectangle fit in target box
-                    if let Some((x, y, rotated)) = tgt_box.find_position_in_box(rect) {
+                    if let Some((x, y, rotated)) = solution.find_position(tgt_idx, rect) {
                         // Create new neighbor
-                        let mut new_solution = solution.clone();
-                        // Remove rectangle from source box 
-                        new_solution.boxes[src_idx].placements.swap_remove(p_idx);
-                        // Insert into target box
-                        new_solution.boxes[tgt_idx].placements.push(Placement { rect, x, y, rotated });
-                        // Remove source box if empty
-                        if new_solution.boxes[src_idx].placements.is_empty() {
-                            new_solution.boxes.swap_remove(src_idx);
-                        }
-                        return Some(new_solution);
+                        let new_solution = move_placement(solution.clone(), src_idx, p_idx, tgt_idx, Placement { rect, x, y, rotated });
+                        return Some((RectInBox::transfer(rect.id, src_box.id, solution.boxes[tgt_idx].id), new_solution));
                     }
                     None
                 }) 
@@ -45,6 +55,156 @@ impl Neighborhood<RectangleSolution> for GeometricNeighborhood {
     }
 }
 
+/// Attribute of geometric moves: rectangle with given id is located in box with given id.
+/// Box ids stay valid when other boxes are removed, box indices don't
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RectInBox {
+    pub rect_id: usize,
+    pub box_id: usize,
+}
+
+impl RectInBox {
+    // Rectangle left source box and entered target box
+    fn transfer(rect_id: usize, src_id: usize, tgt_id: usize) -> MoveAttributes<RectInBox> {
+        MoveAttributes {
+            dropped: RectInBox { rect_id, box_id: src_id },
+            added: RectInBox { rect_id, box_id: tgt_id },
+        }
+    }
+}
+
+/// Number of sampled moves before random_neighbor gives up
+const RANDOM_MOVE_ATTEMPTS: usize = 32;
+
+/// Select a random placement, returns box index and placement index
+fn random_placement(solution: &RectangleSolution, rng: &mut impl Rng) -> (usize, usize) {
+    let src_idx = rng.random_range(0..solution.boxes.len());
+    let p_idx = rng.random_range(0..solution.boxes[src_idx].placements().len());
+    (src_idx, p_idx)
+}
+
+/// Placements as (box index, placement index), with distinct only the first of identical rectangles of a box
+fn placement_indices(solution: &RectangleSolution, distinct: bool) -> Vec<(usize, usize)> {
+    solution.boxes.iter().enumerate()
+        .flat_map(|(box_idx, b)| {
+            (0..b.placements().len())
+                .filter(move |&p_idx| !distinct || first_of_kind(b.placements(), p_idx))
+                .map(move |p_idx| (box_idx, p_idx))
+        })
+        .collect()
+}
+
+/// Transfers (source box, placement, target box) of the placements into all other of num_targets boxes in random order.
+/// Sources are shuffled once, the targets of a source only when it is reached, so no transfer is evaluated in advance
+fn shuffled_transfers(mut sources: Vec<(usize, usize)>, num_targets: usize) -> impl Iterator<Item = (usize, usize, usize)> {
+    sources.shuffle(&mut rng());
+    sources.into_iter().flat_map(move |(src_idx, p_idx)| {
+        let mut targets: Vec<usize> = (0..num_targets).filter(|&tgt_idx| tgt_idx != src_idx).collect();
+        targets.shuffle(&mut rng());
+        targets.into_iter().map(move |tgt_idx| (src_idx, p_idx, tgt_idx))
+    })
+}
+
+/// Pairs i < j of n positions in random order, the partners of i are shuffled only when i is reached
+fn shuffled_pairs(n: usize) -> impl Iterator<Item = (usize, usize)> {
+    let mut firsts: Vec<usize> = (0..n).collect();
+    firsts.shuffle(&mut rng());
+    firsts.into_iter().flat_map(move |i| {
+        let mut seconds: Vec<usize> = (i + 1..n).collect();
+        seconds.shuffle(&mut rng());
+        seconds.into_iter().map(move |j| (i, j))
+    })
+}
+
+/// Solution with the move applied, the given solution stays unchanged
+fn neighbor_of(solution: &RectangleSolution, mut mv: RectMove) -> RectangleSolution {
+    let mut neighbor = solution.clone();
+    mv.apply(&mut neighbor);
+    neighbor
+}
+
+/// True if no placement before p_idx holds an identical rectangle
+fn first_of_kind(placements: &[Placement], p_idx: usize) -> bool {
+    let rect = placements[p_idx].rect;
+    !placements[..p_idx].iter().any(|p| p.rect.is_identical(&rect))
+}
+
+/// Move placement from source box into target box
+fn move_placement(mut new_solution: RectangleSolution, src_idx: usize, p_idx: usize, tgt_idx: usize, placement: Placement) -> RectangleSolution {
+    new_solution.boxes[src_idx].remove(p_idx);
+    new_solution.boxes[tgt_idx].place(placement);
+    // Remove source box if empty
+    if new_solution.boxes[src_idx].placements().is_empty() {
+        new_solution.boxes.swap_remove(src_idx);
+    }
+    new_solution
+}
+
+// ---------------------------------------------------------
+// Box Type Neighborhood
+// ---------------------------------------------------------
+
+/// Change the type of one box. Placements are kept if they fit in the new type,
+/// otherwise the rectangles are packed again with the placement rule of the solution
+pub struct BoxTypeNeighborhood;
+
+impl Neighborhood<RectangleSolution> for BoxTypeNeighborhood {
+    fn neighbors<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectangleSolution> + 'a> {
+        let num_types = solution.instance.box_types.len();
+        let neighbors = (0..solution.boxes.len()).flat_map(move |box_idx| {
+            (0..num_types).filter_map(move |box_type| change_box_type(solution, box_idx, box_type))
+        });
+        Box::new(neighbors)
+    }
+
+    fn random_neighbor(&self, solution: &RectangleSolution) -> Option<RectangleSolution> {
+        let num_types = solution.instance.box_types.len();
+        if solution.boxes.is_empty() || num_types < 2 {
+            return None;
+        }
+        let mut rng = rng();
+        for _ in 0..RANDOM_MOVE_ATTEMPTS {
+            let box_idx = rng.random_range(0..solution.boxes.len());
+            let box_type = rng.random_range(0..num_types);
+            if let Some(neighbor) = change_box_type(solution, box_idx, box_type) {
+                return Some(neighbor);
+            }
+        }
+        None
+    }
+}
+
+/// Solution with the box changed to the type, None if the box has this type already,
+/// the type has no boxes left or the rectangles don't fit
+fn change_box_type(solution: &RectangleSolution, box_idx: usize, box_type: usize) -> Option<RectangleSolution> {
+    let instance = &solution.instance;
+    let bin = &solution.boxes[box_idx];
+    let target = &instance.box_types[box_type];
+    let opened = solution.boxes.iter().filter(|b| b.box_type == box_type).count();
+    if bin.box_type == box_type || target.available.is_some_and(|n| opened >= n) || target.area() < bin.used_area() as u64 {
+        return None;
+    }
+    let mut new_bin = instance.new_box(box_type);
+    if bin.placements().iter().all(|p| p.x + p.width() <= new_bin.width && p.y + p.height() <= new_bin.height) {
+        for &placement in bin.placements() {
+            new_bin.place(placement);
+        }
+    } else {
+        // Pack again, largest rectangles first
+        let mut rects: Vec<Rect> = bin.placements().iter().map(|p| p.rect).collect();
+        rects.sort_by_key(|r| std::cmp::Reverse(r.area()));
+        for rect in rects {
+            let (x, y, rotated) = find_position_in(instance, &new_bin, rect, rect.orientations(), solution.placement)?;
+            new_bin.place(Placement { rect, x, y, rotated });
+        }
+    }
+    // Same box with another type, it keeps its id
+    new_bin.id = bin.id;
+    let mut new_solution = solution.clone();
+    new_solution.boxes[box_idx] = new_bin;
+    Some(new_solution)
+}
+
 // ---------------------------------------------------------
 // Rule based Neighborhood
 // ---------------------------------------------------------
@@ -61,10 +221,67 @@ impl RuleBasedNeighborhood {
 
 impl Neighborhood<PermutationSolution> for RuleBasedNeighborhood {
     fn neighbors<'a>(&'a self, solution: &'a PermutationSolution) -> Box<dyn Iterator<Item = PermutationSolution> + 'a> {
+        Box::new(self.attributed_neighbors(solution).map(|(_, neighbor)| neighbor))
+    }
+
+    fn random_neighbor(&self, solution: &PermutationSolution) -> Option<PermutationSolution> {
+        let n = solution.sequence.len();
+        if n < 2 {
+            return None;
+        }
+        let mut rng = rng();
+        for _ in 0..RANDOM_MOVE_ATTEMPTS {
+            // Select two different random idxs
+            let i = rng.random_range(0..n);
+            let mut j = rng.random_range(0..n - 1);
+            if j >= i {
+                j += 1;
+            }
+            // Swapping identical rectangles keeps the sequence
+            if solution.sequence[i].is_identical(&solution.sequence[j]) {
+                continue;
+            }
+            let mut new_sol = solution.clone();
+            new_sol.sequence.swap(i, j);
+            return Some(new_sol);
+        }
+        None
+    }
+
+    fn shuffled_neighbors<'a>(&'a self, solution: &'a PermutationSolution) -> Box<dyn Iterator<Item = PermutationSolution> + 'a> {
+        // Sampled swaps are in random order already
+        if self.max_swaps.is_some() {
+            return self.neighbors(solution);
+        }
+        let swaps = shuffled_pairs(solution.sequence.len())
+            .filter(|&(i, j)| !solution.sequence[i].is_identical(&solution.sequence[j]))
+            .map(|(i, j)| {
+                let mut new_sol = solution.clone();
+                new_sol.sequence.swap(i, j);
+                new_sol
+            });
+        Box::new(swaps)
+    }
+}
+
+impl AttributedNeighborhood<PermutationSolution> for RuleBasedNeighborhood {
+    // Swapped positions (i, j) with i < j
+    type Attribute = (usize, usize);
+
+    fn attributed_neighbors<'a>(&'a self, solution: &'a PermutationSolution) -> Box<dyn Iterator<Item = (MoveAttributes<(usize, usize)>, PermutationSolution)> + 'a> {
         let n = solution.sequence.len();
         if n < 2 {
             return Box::new(std::iter::empty());
         }
+        // Swapping identical rectangles keeps the sequence
+        let identical = |i: usize, j: usize| solution.sequence[i].is_identical(&solution.sequence[j]);
+        // Swapping the same positions again reverts the move
+        let swap = move |i: usize, j: usize| {
+            let mut new_sol = solution.clone();
+            new_sol.sequence.swap(i, j);
+            let positions = (i.min(j), i.max(j));
+            (MoveAttributes { dropped: positions, added: positions }, new_sol)
+        };
         // Random selection of neighbors if k is set
         if let Some(k) = self.max_swaps {
             let mut rng = rng();
@@ -75,23 +292,17 @@ impl Neighborhood<PermutationSolution> for RuleBasedNeighborhood {
                 let i = rng.random_range(0..n);
                 let j = rng.random_range(0..n);
 
-                if i != j {
-                    let mut new_sol = solution.clone();
-                    new_sol.sequence.swap(i, j);
-                    neighbors.push(new_sol);
+                if i != j && !identical(i, j) {
+                    neighbors.push(swap(i, j));
                 }
             }
-            return Box::new(neighbors.into_iter());
+            Box::new(neighbors.into_iter())
         } else {
             // Without k
             let moves = (0..n).flat_map(move |i| {
-                (i + 1..n).map(move |j| {
-                    let mut new_sol = solution.clone();
-                    new_sol.sequence.swap(i, j);
-                    new_sol
-                })
+                (i + 1..n).filter(move |&j| !identical(i, j)).map(move |j| swap(i, j))
             });
-            return Box::new(moves);
+            Box::new(moves)
         }
     }
 }
@@ -105,46 +316,55 @@ pub struct OverlappingNeighborhood {
 
 impl Neighborhood<RectangleSolution> for OverlappingNeighborhood {
     fn neighbors<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectangleSolution> + 'a> {
+        Box::new(self.attributed_neighbors(solution).map(|(_, neighbor)| neighbor))
+    }
+
+    fn random_neighbor(&self, solution: &RectangleSolution) -> Option<RectangleSolution> {
+        self.random_move(solution).map(|mv| neighbor_of(solution, mv))
+    }
+
+    fn shuffled_neighbors<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectangleSolution> + 'a> {
+        Box::new(self.shuffled_moves(solution).map(move |mv| neighbor_of(solution, mv)))
+    }
+}
+
+impl AttributedNeighborhood<RectangleSolution> for OverlappingNeighborhood {
+    type Attribute = RectInBox;
+
+    fn attributed_neighbors<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = (MoveAttributes<RectInBox>, RectangleSolution)> + 'a> {
         if solution.penalty_factor.is_none() {
             panic!("Penalty factor for Overlapping Neighborhood not set.")
         }
         let moves = solution.boxes.iter().enumerate().flat_map(move |(src_idx, src_box)| {
-            src_box.placements.iter().enumerate().flat_map(move |(p_idx, placement)| {
+            src_box.placements().iter().enumerate().flat_map(move |(p_idx, placement)| {
                 let rect = placement.rect;
 
                 // Move rectangle in existing box
                 let existing_box_moves = solution.boxes.iter().enumerate().filter_map(move |(tgt_idx, tgt_box)| {
                     if src_idx == tgt_idx { return None; }
                     // Search position with allowed overlap
-                    if let Some((x, y, rotated)) = find_position_with_overlap(tgt_box, rect, self.max_overlap_percent) {
-                        let mut new_sol = solution.clone();
+                    if let Some((x, y, rotated)) = find_position_with_overlap(solution, tgt_idx, rect, self.max_overlap_percent) {
                         // Move rectangle
-                        new_sol.boxes[src_idx].placements.swap_remove(p_idx);
-                        new_sol.boxes[tgt_idx].placements.push(Placement { rect, x, y, rotated });
-
-                        if new_sol.boxes[src_idx].placements.is_empty() {
-                            new_sol.boxes.swap_remove(src_idx);
-                        }
-
-                        return Some(new_sol);
+                        let new_sol = move_placement(solution.clone(), src_idx, p_idx, tgt_idx, Placement { rect, x, y, rotated });
+                        return Some((RectInBox::transfer(rect.id, src_box.id, tgt_box.id), new_sol));
                     }
                     None
                 });
                 // Create new box and place rectangle left-bottom
                 // Once with creates iterator with only one element
                 let new_box_move = once_with(move || {
+                    let (box_type, rotated) = solution.new_box_type(rect)?;
                     let mut new_sol = solution.clone();
                     // Remove from source box
-                    new_sol.boxes[src_idx].placements.swap_remove(p_idx);
-                    if new_sol.boxes[src_idx].placements.is_empty() {
+                    new_sol.boxes[src_idx].remove(p_idx);
+                    if new_sol.boxes[src_idx].placements().is_empty() {
                          new_sol.boxes.swap_remove(src_idx);
                     }
                     // Create new box and place rectangle
-                    let mut new_bin = BoxBin::new(solution.instance.box_size);
-                    new_bin.try_place(rect, 0, 0, false);
-                    new_sol.boxes.push(new_bin);
-                    
-                    Some(new_sol)
+                    let mut new_bin = solution.instance.new_box(box_type);
+                    new_bin.try_place(rect, 0, 0, rotated);
+                    let new_id = new_sol.add_box(new_bin);
+                    Some((RectInBox::transfer(rect.id, src_box.id, new_id), new_sol))
                 }).flatten();
 
                 // Combine both iterators, Local Search will first use all exisitng box neighbors
@@ -157,26 +377,18 @@ impl Neighborhood<RectangleSolution> for OverlappingNeighborhood {
     }
 }
 
-fn find_position_with_overlap(bin: &BoxBin, rect: Rect, max_overlap_percent: f64) -> Option<(u32, u32, bool)> {
-    // Create candidates
-    let mut candidates = HashSet::new();
-    candidates.insert((0, 0));
-    for p in &bin.placements {
-        let c1 = (p.x + p.width(), p.y);        // Right top corner of rect
-        let c2 = (p.x, p.y + p.height());       // Left top corner of rect
-        if c1.0 < bin.capacity && c1.1 < bin.capacity { candidates.insert(c1); }
-        if c2.0 < bin.capacity && c2.1 < bin.capacity { candidates.insert(c2); }
+/// Position in the target box with allowed overlap. Overlapping rectangles can't be separated by guillotine cuts,
+/// so guillotine mode allows no overlap and uses the guillotine position search of the solution
+fn find_position_with_overlap(solution: &RectangleSolution, tgt_idx: usize, rect: Rect, max_overlap_percent: f64) -> Option<(u32, u32, bool)> {
+    if solution.instance.guillotine {
+        return solution.find_position(tgt_idx, rect);
     }
-    
-    let mut sorted_candidates: Vec<(u32, u32)> = candidates.into_iter().collect();
-    sorted_candidates.sort_by(|a, b| if a.1 != b.1 { a.1.cmp(&b.1) } else { a.0.cmp(&b.0) });
-
-    for (x, y) in sorted_candidates {
-        if check_overlap_limit(bin, rect, x, y, false, max_overlap_percent) {
-            return Some((x, y, false));
-        }
-        if check_overlap_limit(bin, rect, x, y, true, max_overlap_percent) {
-            return Some((x, y, true));
+    let bin = &solution.boxes[tgt_idx];
+    for (x, y) in bin.candidate_positions(solution.placement) {
+        for &rotated in rect.orientations() {
+            if check_overlap_limit(bin, rect, x, y, rotated, max_overlap_percent) {
+                return Some((x, y, rotated));
+            }
         }
     }
     None
@@ -186,13 +398,13 @@ fn check_overlap_limit(bin: &BoxBin, rect: Rect, x: u32, y: u32, rotated: bool,
     let w = if rotated { rect.height } else { rect.width };
     let h = if rotated { rect.width } else { rect.height };
     
-    if x + w > bin.capacity || y + h > bin.capacity {
+    if x + w > bin.width || y + h > bin.height {
         return false;
     }
 
     let candidate = Placement { rect, x, y, rotated };
     
-    for existing in &bin.placements {
+    for existing in bin.placements() {
         let intersection = candidate.intersection_area(existing);
         if intersection > 0 {
             let max_area = candidate.rect.area().max(existing.rect.area());
@@ -206,3 +418,469 @@ fn check_overlap_limit(bin: &BoxBin, rect: Rect, x: u32, y: u32, rotated: bool,
     true
 }
 
+
+// ---------------------------------------------------------
+// Move based Neighborhoods
+// ---------------------------------------------------------
+
+/// Move a placed rectangle from source box into target box
+#[derive(Clone, Copy, Debug)]
+pub struct RectMove {
+    pub src_idx: usize,
+    pub p_idx: usize,
+    // Index boxes.len() opens a new box
+    pub tgt_idx: usize,
+    // Box type of an opened box
+    pub tgt_type: usize,
+    // Placement before and after the move
+    pub from: Placement,
+    pub to: Placement,
+    // Set by apply, needed for undo. Removed box as (box type, box id)
+    opened_box: bool,
+    removed_box: Option<(usize, usize)>,
+}
+
+impl RectMove {
+    pub fn new(src_idx: usize, p_idx: usize, tgt_idx: usize, from: Placement, to: Placement) -> Self {
+        Self { src_idx, p_idx, tgt_idx, tgt_type: 0, from, to, opened_box: false, removed_box: None }
+    }
+
+    // Move into a new box of the given type
+    pub fn to_new_box(solution: &RectangleSolution, src_idx: usize, p_idx: usize, box_type: usize, from: Placement, to: Placement) -> Self {
+        Self { tgt_type: box_type, ..Self::new(src_idx, p_idx, solution.boxes.len(), from, to) }
+    }
+}
+
+impl Move<RectangleSolution> for RectMove {
+    // Change of total box cost and score
+    type Delta = (isize, i64);
+
+    fn delta_cost(&self, solution: &RectangleSolution) -> Self::Delta {
+        let src_box = &solution.boxes[self.src_idx];
+        let tgt_box = solution.boxes.get(self.tgt_idx);
+        let area = self.from.rect.area() as i64;
+        let src_area = src_box.used_area() as i64;
+        let tgt_area = tgt_box.map_or(0, |b| b.used_area() as i64);
+        // Only the squares of source and target box change
+        let score_delta = (src_area.pow(2) - (src_area - area).pow(2))
+            + (tgt_area.pow(2) - (tgt_area + area).pow(2));
+        let box_types = &solution.instance.box_types;
+        let mut box_delta = 0;
+        if src_box.placements().len() == 1 { box_delta -= box_types[src_box.box_type].cost as isize; }
+        if tgt_box.is_none() { box_delta += box_types[self.tgt_type].cost as isize; }
+
+        if let Some(penalty_factor) = solution.penalty_factor {
+            // Overlap changes only between moved rectangle and source and target box
+            let removed_overlap: u32 = src_box.placements().iter().enumerate()
+                .filter(|(idx, _)| *idx != self.p_idx)
+                .map(|(_, p)| self.from.intersection_area(p))
+                .sum();
+            let added_overlap: u32 = tgt_box.map_or(0, |b| b.placements().iter().map(|p| self.to.intersection_area(p)).sum());
+            let penalty_delta = added_overlap as i64 - removed_overlap as i64;
+            (0, penalty_delta * penalty_factor + score_delta + box_delta as i64 * solution.box_weight())
+        } else {
+            (box_delta, score_delta)
+        }
+    }
+
+    fn apply(&mut self, solution: &mut RectangleSolution) {
+        self.opened_box = self.tgt_idx == solution.boxes.len();
+        if self.opened_box {
+            let bin = solution.instance.new_box(self.tgt_type);
+            solution.add_box(bin);
+        }
+        solution.boxes[self.src_idx].remove(self.p_idx);
+        solution.boxes[self.tgt_idx].place(self.to);
+        // Remove source box if empty, its type and id are kept for undo
+        self.removed_box = None;
+        if solution.boxes[self.src_idx].placements().is_empty() {
+            let removed = solution.boxes.swap_remove(self.src_idx);
+            self.removed_box = Some((removed.box_type, removed.id));
+        }
+    }
+
+    fn undo(&mut self, solution: &mut RectangleSolution) {
+        // Revert swap_remove of the source box
+        if let Some((box_type, id)) = self.removed_box {
+            let mut bin = solution.instance.new_box(box_type);
+            bin.id = id;
+            solution.boxes.push(bin);
+            let last = solution.boxes.len() - 1;
+            solution.boxes.swap(self.src_idx, last);
+        }
+        solution.boxes[self.tgt_idx].pop();
+        solution.boxes[self.src_idx].restore(self.p_idx, self.from);
+
+        if self.opened_box {
+            solution.boxes.pop();
+        }
+    }
+}
+
+impl MoveNeighborhood<RectangleSolution> for GeometricNeighborhood {
+    type Move = RectMove;
+
+    fn moves<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectMove> + 'a> {
+        // Identical rectangles of a box give moves of equal cost
+        let moves = solution.boxes.iter().enumerate().flat_map(move |(src_idx, src_box)| {
+            let distinct = (0..src_box.placements().len()).filter(|&p_idx| first_of_kind(src_box.placements(), p_idx));
+            distinct.flat_map(move |p_idx| {
+                (0..solution.boxes.len()).filter_map(move |tgt_idx| geometric_move(solution, src_idx, p_idx, tgt_idx))
+            })
+        });
+
+        Box::new(moves)
+    }
+
+    fn random_move(&self, solution: &RectangleSolution) -> Option<RectMove> {
+        if solution.boxes.len() < 2 {
+            return None;
+        }
+        let mut rng = rng();
+        // Sample random moves until one fits
+        for _ in 0..RANDOM_MOVE_ATTEMPTS {
+            let (src_idx, p_idx) = random_placement(solution, &mut rng);
+            let tgt_idx = rng.random_range(0..solution.boxes.len());
+            if let Some(mv) = geometric_move(solution, src_idx, p_idx, tgt_idx) {
+                return Some(mv);
+            }
+        }
+        None
+    }
+
+    fn shuffled_moves<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectMove> + 'a> {
+        let transfers = shuffled_transfers(placement_indices(solution, true), solution.boxes.len());
+        Box::new(transfers.filter_map(move |(src_idx, p_idx, tgt_idx)| geometric_move(solution, src_idx, p_idx, tgt_idx)))
+    }
+}
+
+/// Move of the placement into another existing box at the position of the placement rule, None if it doesn't fit
+fn geometric_move(solution: &RectangleSolution, src_idx: usize, p_idx: usize, tgt_idx: usize) -> Option<RectMove> {
+    if src_idx == tgt_idx {
+        return None;
+    }
+    let from = solution.boxes[src_idx].placements()[p_idx];
+    let (x, y, rotated) = solution.find_position(tgt_idx, from.rect)?;
+    Some(RectMove::new(src_idx, p_idx, tgt_idx, from, Placement { rect: from.rect, x, y, rotated }))
+}
+
+impl MoveNeighborhood<RectangleSolution> for OverlappingNeighborhood {
+    type Move = RectMove;
+
+    fn moves<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectMove> + 'a> {
+        if solution.penalty_factor.is_none() {
+            panic!("Penalty factor for Overlapping Neighborhood not set.")
+        }
+        // Target index behind all existing boxes is a new box, it is tried after all existing boxes
+        let moves = solution.boxes.iter().enumerate().flat_map(move |(src_idx, src_box)| {
+            (0..src_box.placements().len()).flat_map(move |p_idx| {
+                (0..=solution.boxes.len()).filter_map(move |tgt_idx| self.overlapping_move(solution, src_idx, p_idx, tgt_idx))
+            })
+        });
+
+        Box::new(moves)
+    }
+
+    fn random_move(&self, solution: &RectangleSolution) -> Option<RectMove> {
+        if solution.penalty_factor.is_none() {
+            panic!("Penalty factor for Overlapping Neighborhood not set.")
+        }
+        if solution.boxes.is_empty() {
+            return None;
+        }
+        let mut rng = rng();
+        for _ in 0..RANDOM_MOVE_ATTEMPTS {
+            let (src_idx, p_idx) = random_placement(solution, &mut rng);
+            // Index behind last box denotes a new box
+            let tgt_idx = rng.random_range(0..=solution.boxes.len());
+            if let Some(mv) = self.overlapping_move(solution, src_idx, p_idx, tgt_idx) {
+                return Some(mv);
+            }
+        }
+        None
+    }
+
+    fn shuffled_moves<'a>(&'a self, solution: &'a RectangleSolution) -> Box<dyn Iterator<Item = RectMove> + 'a> {
+        if solution.penalty_factor.is_none() {
+            panic!("Penalty factor for Overlapping Neighborhood not set.")
+        }
+        let transfers = shuffled_transfers(placement_indices(solution, false), solution.boxes.len() + 1);
+        Box::new(transfers.filter_map(move |(src_idx, p_idx, tgt_idx)| self.overlapping_move(solution, src_idx, p_idx, tgt_idx)))
+    }
+}
+
+impl OverlappingNeighborhood {
+    /// Move of the placement into another box with allowed overlap, target index boxes.len() opens a new box
+    /// with the placement left-bottom. None if it doesn't fit
+    fn overlapping_move(&self, solution: &RectangleSolution, src_idx: usize, p_idx: usize, tgt_idx: usize) -> Option<RectMove> {
+        if src_idx == tgt_idx {
+            return None;
+        }
+        let from = solution.boxes[src_idx].placements()[p_idx];
+        let rect = from.rect;
+        if tgt_idx == solution.boxes.len() {
+            let (box_type, rotated) = solution.new_box_type(rect)?;
+            return Some(RectMove::to_new_box(solution, src_idx, p_idx, box_type, from, Placement { rect, x: 0, y: 0, rotated }));
+        }
+        let (x, y, rotated) = find_position_with_overlap(solution, tgt_idx, rect, self.max_overlap_percent)?;
+        Some(RectMove::new(src_idx, p_idx, tgt_idx, from, Placement { rect, x, y, rotated }))
+    }
+}
+
+/// Swap two positions of the rectangle sequence
+#[derive(Clone, Debug)]
+pub struct SwapMove {
+    pub i: usize,
+    pub j: usize,
+    // Cost of the solution the move was created for
+    base_cost: (usize, i64),
+    // Boxes of the rectangles in front of both positions, the swap doesn't change them
+    prefix: Rc<Vec<BoxBin>>,
+}
+
+impl Move<PermutationSolution> for SwapMove {
+    type Delta = (isize, i64);
+
+    // First fit continues from the prefix boxes, only the rectangles from the first swapped position are decoded
+    fn delta_cost(&self, solution: &PermutationSolution) -> Self::Delta {
+        let (first, second) = (self.i.min(self.j), self.i.max(self.j));
+        let sequence = &solution.sequence;
+        let swapped = (first..sequence.len()).map(|idx| {
+            if idx == first { sequence[second] } else if idx == second { sequence[first] } else { sequence[idx] }
+        });
+        let (num_boxes, score) = solution.decode_cost_from(self.prefix.to_vec(), swapped);
+        (num_boxes as isize - self.base_cost.0 as isize, score - self.base_cost.1)
+    }
+
+    fn apply(&mut self, solution: &mut PermutationSolution) {
+        solution.sequence.swap(self.i, self.j);
+    }
+
+    fn undo(&mut self, solution: &mut PermutationSolution) {
+        solution.sequence.swap(self.i, self.j);
+    }
+}
+
+/// Boxes of the sequence prefixes of length 0, 1, .., each packed from the one before when it is reached
+fn sequence_prefixes(solution: &PermutationSolution) -> impl Iterator<Item = Rc<Vec<BoxBin>>> + '_ {
+    let mut boxes = Vec::new();
+    solution.sequence.iter().map(move |&rect| {
+        let prefix = Rc::new(boxes.clone());
+        boxes = first_fit_from(&solution.instance, solution.placement, solution.box_selection, std::mem::take(&mut boxes), once((rect, None)));
+        prefix
+    })
+}
+
+/// Swap moves of the pairs, prefixes are decoded once up to the last first position
+fn swap_moves(solution: &PermutationSolution, pairs: Vec<(usize, usize)>) -> Vec<SwapMove> {
+    let base_cost = solution.cost();
+    let len = pairs.iter().map(|&(i, j)| i.min(j) + 1).max().unwrap_or(0);
+    let prefixes: Vec<Rc<Vec<BoxBin>>> = sequence_prefixes(solution).take(len).collect();
+    pairs.into_iter()
+        .map(|(i, j)| SwapMove { i, j, base_cost, prefix: Rc::clone(&prefixes[i.min(j)]) })
+        .collect()
+}
+
+impl MoveNeighborhood<PermutationSolution> for RuleBasedNeighborhood {
+    type Move = SwapMove;
+
+    fn moves<'a>(&'a self, solution: &'a PermutationSolution) -> Box<dyn Iterator<Item = SwapMove> + 'a> {
+        let n = solution.sequence.len();
+        if n < 2 {
+            return Box::new(std::iter::empty());
+        }
+        // Swapping identical rectangles keeps the sequence, its cost is not evaluated
+        let identical = |i: usize, j: usize| solution.sequence[i].is_identical(&solution.sequence[j]);
+        // Random selection of moves if k is set
+        if let Some(k) = self.max_swaps {
+            let mut rng = rng();
+            let pairs: Vec<(usize, usize)> = (0..k)
+                .map(|_| (rng.random_range(0..n), rng.random_range(0..n)))
+                .filter(|&(i, j)| i != j && !identical(i, j))
+                .collect();
+            Box::new(swap_moves(solution, pairs).into_iter())
+        } else {
+            // Moves with first position i share the prefix in front of i
+            let base_cost = solution.cost();
+            Box::new(sequence_prefixes(solution).enumerate().flat_map(move |(i, prefix)| {
+                (i + 1..n).filter(move |&j| !identical(i, j))
+                    .map(move |j| SwapMove { i, j, base_cost, prefix: Rc::clone(&prefix) })
+            }))
+        }
+    }
+
+    fn random_move(&self, solution: &PermutationSolution) -> Option<SwapMove> {
+        self.sampled_moves(solution, 1).next()
+    }
+
+    fn sampled_moves<'a>(&'a self, solution: &'a PermutationSolution, k: usize) -> Box<dyn Iterator<Item = SwapMove> + 'a> {
+        let n = solution.sequence.len();
+        if n < 2 {
+            return Box::new(std::iter::empty());
+        }
+        // Cost of the unchanged sequence and the prefixes are decoded once for all samples
+        let mut rng = rng();
+        let pairs: Vec<(usize, usize)> = (0..k)
+            .filter_map(|_| {
+                // Sample random pairs until the rectangles differ
+                (0..RANDOM_MOVE_ATTEMPTS)
+                    .map(|_| (rng.random_range(0..n), rng.random_range(0..n)))
+                    .find(|&(i, j)| i != j && !solution.sequence[i].is_identical(&solution.sequence[j]))
+            })
+            .collect();
+        Box::new(swap_moves(solution, pairs).into_iter())
+    }
+
+    fn shuffled_moves<'a>(&'a self, solution: &'a PermutationSolution) -> Box<dyn Iterator<Item = SwapMove> + 'a> {
+        // Sampled swaps are in random order already
+        if self.max_swaps.is_some() || solution.sequence.len() < 2 {
+            return self.moves(solution);
+        }
+        let base_cost = solution.cost();
+        // Pairs come grouped by their first position, its prefix is decoded when the group is reached
+        let mut current: Option<(usize, Rc<Vec<BoxBin>>)> = None;
+        let moves = shuffled_pairs(solution.sequence.len())
+            .filter(|&(i, j)| !solution.sequence[i].is_identical(&solution.sequence[j]))
+            .map(move |(i, j)| {
+                if current.as_ref().is_none_or(|(first, _)| *first != i) {
+                    current = Some((i, Rc::new(solution.decode_prefix(i))));
+                }
+                let prefix = Rc::clone(&current.as_ref().expect("prefix is set").1);
+                SwapMove { i, j, base_cost, prefix }
+            });
+        Box::new(moves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::greedy;
+    use crate::generator::Generator;
+    use crate::problem::greedy::{RectangleGreedyState, SortByAreaStrategy};
+    use crate::problem::instance::Instance;
+    use crate::problem::rect::RectType;
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+
+    // Greedy solution with identical rects in the first box and free space in the second
+    fn demand_solution() -> RectangleSolution {
+        let rect_types = [RectType::new(20, 20, 2), RectType::new(10, 10, 6), RectType::new(15, 5, 4)];
+        let instance = Arc::new(Instance::from_demands(40, 40, &rect_types));
+        let mut state = RectangleGreedyState::new(instance);
+        greedy::solve(&mut state, &mut SortByAreaStrategy);
+        state.solution
+    }
+
+    #[test]
+    fn geometric_dedup_keeps_neighbor_costs() {
+        let solution = demand_solution();
+        let costs: Vec<_> = GeometricNeighborhood.neighbors(&solution).map(|n| n.cost()).collect();
+        // Every placement into every other box
+        let all_costs: Vec<_> = placement_indices(&solution, false).into_iter()
+            .flat_map(|(src_idx, p_idx)| (0..solution.boxes.len()).map(move |tgt_idx| (src_idx, p_idx, tgt_idx)))
+            .filter(|&(src_idx, _, tgt_idx)| src_idx != tgt_idx)
+            .filter_map(|(src_idx, p_idx, tgt_idx)| {
+                let rect = solution.boxes[src_idx].placements()[p_idx].rect;
+                let (x, y, rotated) = solution.find_position(tgt_idx, rect)?;
+                Some(move_placement(solution.clone(), src_idx, p_idx, tgt_idx, Placement { rect, x, y, rotated }).cost())
+            })
+            .collect();
+        assert!(costs.len() < all_costs.len());
+        assert_eq!(costs.iter().min(), all_costs.iter().min());
+        assert_eq!(costs.into_iter().collect::<BTreeSet<_>>(), all_costs.into_iter().collect::<BTreeSet<_>>());
+    }
+
+    #[test]
+    fn permutation_dedup_keeps_neighbor_costs() {
+        let instance = Arc::new(Generator::generate_with_demands(4, (2, 4), (5, 20), (5, 20), 40, 40));
+        let solution = PermutationSolution::new(Arc::clone(&instance), instance.rects.clone());
+        let costs: Vec<_> = RuleBasedNeighborhood::new(None).neighbors(&solution).map(|n| n.cost()).collect();
+        // Every swap of two positions
+        let n = solution.sequence.len();
+        let all_costs: Vec<_> = (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let mut neighbor = solution.clone();
+                neighbor.sequence.swap(i, j);
+                neighbor.cost()
+            })
+            .collect();
+        assert!(costs.len() < all_costs.len());
+        assert_eq!(costs.iter().min(), all_costs.iter().min());
+        assert_eq!(costs.into_iter().collect::<BTreeSet<_>>(), all_costs.into_iter().collect::<BTreeSet<_>>());
+    }
+
+    // Box ids, types and placements in order, equal if the solution is unchanged
+    fn layout(solution: &RectangleSolution) -> Vec<(usize, usize, Vec<Placement>)> {
+        solution.boxes.iter().map(|b| (b.id, b.box_type, b.placements().to_vec())).collect()
+    }
+
+    fn cost_change(before: (usize, i64), after: (usize, i64)) -> (isize, i64) {
+        (after.0 as isize - before.0 as isize, after.1 - before.1)
+    }
+
+    // Every move: delta is the change of the cost, undo restores the solution. Returns the number of moves
+    fn check_rect_moves(mut solution: RectangleSolution, neighborhood: &impl MoveNeighborhood<RectangleSolution, Move = RectMove>) -> usize {
+        let before = (solution.cost(), layout(&solution));
+        let moves: Vec<RectMove> = neighborhood.moves(&solution).collect();
+        let num_moves = moves.len();
+        for mut mv in moves {
+            let delta = mv.delta_cost(&solution);
+            mv.apply(&mut solution);
+            assert_eq!(delta, cost_change(before.0, solution.cost()));
+            mv.undo(&mut solution);
+            assert_eq!((solution.cost(), layout(&solution)), before);
+        }
+        num_moves
+    }
+
+    // One box per rect, every move empties its source box
+    fn trivial_solution(instance: Arc<Instance>) -> RectangleSolution {
+        let mut solution = RectangleSolution::new(Arc::clone(&instance));
+        for &rect in &instance.rects {
+            solution.open_box(rect);
+        }
+        solution
+    }
+
+    #[test]
+    fn geometric_moves_delta_and_undo() {
+        let instance = Arc::new(Generator::generate_instance(15, (5, 20), (5, 20), 40, 40));
+        let mut state = RectangleGreedyState::new(Arc::clone(&instance));
+        greedy::solve(&mut state, &mut SortByAreaStrategy);
+        check_rect_moves(state.solution, &GeometricNeighborhood);
+        assert!(check_rect_moves(trivial_solution(instance), &GeometricNeighborhood) > 0);
+    }
+
+    #[test]
+    fn overlapping_moves_delta_and_undo() {
+        let instance = Arc::new(Generator::generate_instance(12, (5, 20), (5, 20), 40, 40));
+        let mut state = RectangleGreedyState::new(Arc::clone(&instance));
+        greedy::solve(&mut state, &mut SortByAreaStrategy);
+        let neighborhood = OverlappingNeighborhood { max_overlap_percent: 0.5 };
+        check_rect_moves(state.solution.with_penalty(10), &neighborhood);
+        assert!(check_rect_moves(trivial_solution(instance).with_penalty(10), &neighborhood) > 0);
+    }
+
+    #[test]
+    fn swap_moves_delta_and_undo() {
+        let instance = Arc::new(Generator::generate_instance(15, (5, 20), (5, 20), 40, 40));
+        let mut solution = PermutationSolution::new(Arc::clone(&instance), instance.rects.clone());
+        let before = solution.cost();
+        let ids = |solution: &PermutationSolution| solution.sequence.iter().map(|r| r.id).collect::<Vec<_>>();
+        let sequence = ids(&solution);
+        let neighborhood = RuleBasedNeighborhood::new(None);
+        let moves: Vec<SwapMove> = neighborhood.moves(&solution)
+            .chain(neighborhood.shuffled_moves(&solution))
+            .chain(neighborhood.sampled_moves(&solution, 20))
+            .collect();
+        assert!(!moves.is_empty());
+        for mut mv in moves {
+            let delta = mv.delta_cost(&solution);
+            mv.apply(&mut solution);
+            assert_eq!(delta, cost_change(before, solution.cost()));
+            mv.undo(&mut solution);
+            assert_eq!(ids(&solution), sequence);
+        }
+    }
+}
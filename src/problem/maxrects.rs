@@ -0,0 +1,240 @@
+use super::rect::Rect;
+use super::solution::{BoxBin, Placement};
+use std::fmt;
+
+/// Scoring of free rectangles, the position with the smallest score is chosen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaxRectsHeuristic {
+    // Smallest leftover of the shorter side
+    BestShortSideFit,
+    // Smallest leftover of the longer side
+    BestLongSideFit,
+    // Smallest free rectangle
+    BestAreaFit,
+    // Lowest top border, then leftmost
+    BottomLeft,
+    // Longest border shared with the box and placed rectangles
+    ContactPoint,
+}
+
+impl fmt::Display for MaxRectsHeuristic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaxRectsHeuristic::BestShortSideFit => write!(f, "BSSF"),
+            MaxRectsHeuristic::BestLongSideFit => write!(f, "BLSF"),
+            MaxRectsHeuristic::BestAreaFit => write!(f, "BAF"),
+            MaxRectsHeuristic::BottomLeft => write!(f, "BL"),
+            MaxRectsHeuristic::ContactPoint => write!(f, "CP"),
+        }
+    }
+}
+
+// Compared lexicographically, second value breaks ties
+pub(crate) type Score = (i64, i64);
+
+/// Free rectangle in a box, x and y denote the left-bottom corner
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FreeRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FreeRect {
+    fn contains(&self, other: &FreeRect) -> bool {
+        other.x >= self.x && other.y >= self.y
+            && other.x + other.width <= self.x + self.width
+            && other.y + other.height <= self.y + self.height
+    }
+}
+
+/// Free space of a box as list of maximal free rectangles, they may overlap each other
+#[derive(Clone, Debug)]
+pub struct MaxRects {
+    pub width: u32,
+    pub height: u32,
+    pub free: Vec<FreeRect>,
+}
+
+impl MaxRects {
+    // Empty box
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, free: vec![FreeRect { x: 0, y: 0, width, height }] }
+    }
+
+    // Box without free space, free rectangles are added with add_free
+    pub fn full(width: u32, height: u32) -> Self {
+        Self { width, height, free: Vec::new() }
+    }
+
+    /// Free space of a box with its current placements
+    pub fn from_bin(bin: &BoxBin) -> Self {
+        let mut max_rects = Self::new(bin.width, bin.height);
+        for placement in bin.placements() {
+            max_rects.place(placement);
+        }
+        max_rects
+    }
+
+    /// Split all free rectangles intersecting the placement into their maximal remainders
+    pub fn place(&mut self, placement: &Placement) {
+        let (px, py) = (placement.x, placement.y);
+        let (px2, py2) = (px + placement.width(), py + placement.height());
+        let mut split = Vec::new();
+        self.free.retain(|f| {
+            let (fx2, fy2) = (f.x + f.width, f.y + f.height);
+            if px >= fx2 || px2 <= f.x || py >= fy2 || py2 <= f.y {
+                return true;
+            }
+            // Left, right, bottom and top remainder
+            if px > f.x { split.push(FreeRect { width: px - f.x, ..*f }); }
+            if px2 < fx2 { split.push(FreeRect { x: px2, width: fx2 - px2, ..*f }); }
+            if py > f.y { split.push(FreeRect { height: py - f.y, ..*f }); }
+            if py2 < fy2 { split.push(FreeRect { y: py2, height: fy2 - py2, ..*f }); }
+            false
+        });
+        self.free.extend(split);
+        self.prune();
+    }
+
+    // Remove free rectangles contained in others, of duplicates the first one is kept
+    fn prune(&mut self) {
+        let free = &self.free;
+        let keep: Vec<bool> = (0..free.len())
+            .map(|i| !(0..free.len()).any(|j| i != j && free[j].contains(&free[i]) && (free[i] != free[j] || j < i)))
+            .collect();
+        let mut keep = keep.into_iter();
+        self.free.retain(|_| keep.next().unwrap_or(true));
+    }
+
+    /// Left-bottom corners of the free rectangles sorted by y then x
+    pub fn corners(&self) -> Vec<(u32, u32)> {
+        let mut corners: Vec<(u32, u32)> = self.free.iter().map(|f| (f.x, f.y)).collect();
+        corners.sort_by_key(|&(x, y)| (y, x));
+        corners.dedup();
+        corners
+    }
+
+    /// Best position of the heuristic, rect is placed in the left-bottom corner of a free rectangle
+    pub fn find_position(&self, bin: &BoxBin, rect: Rect, orientations: &[bool], heuristic: MaxRectsHeuristic) -> Option<(u32, u32, bool)> {
+        self.best_fit(rect, orientations, |f, w, h| {
+            let (left_w, left_h) = ((f.width - w) as i64, (f.height - h) as i64);
+            match heuristic {
+                MaxRectsHeuristic::BestShortSideFit => (left_w.min(left_h), left_w.max(left_h)),
+                MaxRectsHeuristic::BestLongSideFit => (left_w.max(left_h), left_w.min(left_h)),
+                MaxRectsHeuristic::BestAreaFit => ((f.width * f.height) as i64 - (w * h) as i64, left_w.min(left_h)),
+                MaxRectsHeuristic::BottomLeft => ((f.y + h) as i64, f.x as i64),
+                MaxRectsHeuristic::ContactPoint => (-(contact(bin, f.x, f.y, w, h) as i64), (f.y as i64)),
+            }
+        })
+    }
+
+    /// Free rectangle and orientation with the smallest score of (free rectangle, width, height)
+    pub(crate) fn best_fit(&self, rect: Rect, orientations: &[bool], score: impl Fn(&FreeRect, u32, u32) -> Score) -> Option<(u32, u32, bool)> {
+        // Score and position of the best fit
+        let mut best: Option<(Score, (u32, u32, bool))> = None;
+        for f in &self.free {
+            for &rotated in orientations {
+                let (w, h) = if rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
+                if w > f.width || h > f.height {
+                    continue;
+                }
+                let score = score(f, w, h);
+                if best.is_none_or(|(best_score, _)| score < best_score) {
+                    best = Some((score, (f.x, f.y, rotated)));
+                }
+            }
+        }
+        best.map(|(_, position)| position)
+    }
+
+    /// Add a free rectangle, unless it is contained in another one
+    pub fn add_free(&mut self, rect: FreeRect) {
+        self.free.push(rect);
+        self.prune();
+    }
+}
+
+/// Length of the border of rectangle (x, y, w, h) touching the box border or placed rectangles
+fn contact(bin: &BoxBin, x: u32, y: u32, w: u32, h: u32) -> u32 {
+    // Length of the common part of two intervals
+    let common = |a1: u32, a2: u32, b1: u32, b2: u32| a2.min(b2).saturating_sub(a1.max(b1));
+    let mut length = 0;
+    if x == 0 { length += h; }
+    if x + w == bin.width { length += h; }
+    if y == 0 { length += w; }
+    if y + h == bin.height { length += w; }
+    for p in bin.placements() {
+        let (px2, py2) = (p.x + p.width(), p.y + p.height());
+        if px2 == x || p.x == x + w {
+            length += common(y, y + h, p.y, py2);
+        }
+        if py2 == y || p.y == y + h {
+            length += common(x, x + w, p.x, px2);
+        }
+    }
+    length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+    use crate::problem::solution::PlacementRule;
+
+    const HEURISTICS: [MaxRectsHeuristic; 5] = [
+        MaxRectsHeuristic::BestShortSideFit, MaxRectsHeuristic::BestLongSideFit, MaxRectsHeuristic::BestAreaFit,
+        MaxRectsHeuristic::BottomLeft, MaxRectsHeuristic::ContactPoint,
+    ];
+
+    fn sorted(free: &[FreeRect]) -> Vec<(u32, u32, u32, u32)> {
+        let mut free: Vec<_> = free.iter().map(|f| (f.x, f.y, f.width, f.height)).collect();
+        free.sort_unstable();
+        free
+    }
+
+    #[test]
+    fn placements_are_in_bounds_and_disjoint() {
+        let rects = Generator::generate_instance(40, (3, 15), (3, 15), 40, 30).rects;
+        for heuristic in HEURISTICS {
+            let mut bin = BoxBin::new(40, 30);
+            let mut max_rects = MaxRects::new(40, 30);
+            for &rect in &rects {
+                let Some((x, y, rotated)) = max_rects.find_position(&bin, rect, rect.orientations(), heuristic) else { continue };
+                let placement = Placement { rect, x, y, rotated };
+                assert!(x + placement.width() <= bin.width && y + placement.height() <= bin.height, "{} out of bounds", heuristic);
+                assert!(bin.placements().iter().all(|p| p.intersection_area(&placement) == 0), "{} overlaps", heuristic);
+                max_rects.place(&placement);
+                bin.place(placement);
+            }
+            assert!(bin.placements().len() > 1);
+        }
+    }
+
+    #[test]
+    fn free_list_follows_placements() {
+        let rects = Generator::generate_instance(40, (3, 15), (3, 15), 40, 30).rects;
+        let mut bin = BoxBin::new(40, 30);
+        let mut max_rects = MaxRects::new(40, 30);
+        for &rect in &rects {
+            let rule = PlacementRule::MaxRects(MaxRectsHeuristic::BestShortSideFit);
+            // Free list cached by the box gives the same position as a new one
+            let position = bin.find_position_with(rect, rect.orientations(), rule);
+            assert_eq!(position, MaxRects::from_bin(&bin).find_position(&bin, rect, rect.orientations(), MaxRectsHeuristic::BestShortSideFit));
+            let Some((x, y, rotated)) = position else { continue };
+            let placement = Placement { rect, x, y, rotated };
+            max_rects.place(&placement);
+            bin.place(placement);
+
+            // Free rects are maximal, inside the box and don't touch any placement
+            assert_eq!(sorted(&max_rects.free), sorted(&MaxRects::from_bin(&bin).free));
+            for f in &max_rects.free {
+                assert!(f.x + f.width <= bin.width && f.y + f.height <= bin.height);
+                let free = Placement { rect: Rect::new(usize::MAX, f.width, f.height), x: f.x, y: f.y, rotated: false };
+                assert!(bin.placements().iter().all(|p| p.intersection_area(&free) == 0));
+            }
+            assert!(!max_rects.free.iter().any(|f| f.contains(&FreeRect { x, y, width: placement.width(), height: placement.height() })));
+        }
+    }
+}
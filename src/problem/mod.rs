@@ -2,4 +2,16 @@ pub mod rect;
 pub mod instance;
 pub mod solution;
 pub mod greedy;
+pub mod guillotine;
 pub mod local_search;
+pub mod maxrects;
+pub mod genetic;
+pub mod brkga;
+pub mod alns;
+pub mod beam_search;
+pub mod bounds;
+pub mod branch_and_bound;
+pub mod feasibility;
+pub mod perturbation;
+pub mod skyline;
+pub mod validation;
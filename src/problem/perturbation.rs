@@ -0,0 +1,114 @@
+use crate::algorithms::iterated_local_search::Perturbation;
+use super::solution::{RectangleSolution, PermutationSolution, Placement};
+use rand::{Rng, rng};
+use rand::seq::SliceRandom;
+
+// ---------------------------------------------------------
+// Perturbations for RectangleSolution
+// ---------------------------------------------------------
+
+/// Move k random rectangles into new boxes
+pub struct ScatterRects {
+    pub k: usize,
+}
+
+impl Perturbation<RectangleSolution> for ScatterRects {
+    fn perturb(&self, solution: &RectangleSolution) -> RectangleSolution {
+        let mut new_sol = solution.clone();
+        let mut rng = rng();
+        for _ in 0..self.k {
+            if new_sol.boxes.is_empty() {
+                break;
+            }
+            let src_idx = rng.random_range(0..new_sol.boxes.len());
+            let p_idx = rng.random_range(0..new_sol.boxes[src_idx].placements().len());
+            let rect = new_sol.boxes[src_idx].remove(p_idx).rect;
+            if new_sol.boxes[src_idx].placements().is_empty() {
+                new_sol.boxes.swap_remove(src_idx);
+            }
+            // Place it left-bottom in a new box
+            new_sol.open_box(rect);
+        }
+        new_sol
+    }
+}
+
+/// Remove the box with least used area and insert its rectangles into the other boxes
+pub struct DissolveBox;
+
+impl Perturbation<RectangleSolution> for DissolveBox {
+    fn perturb(&self, solution: &RectangleSolution) -> RectangleSolution {
+        let mut new_sol = solution.clone();
+        let Some(idx) = (0..new_sol.boxes.len()).min_by_key(|&i| new_sol.boxes[i].used_area()) else {
+            return new_sol;
+        };
+        let dissolved = new_sol.boxes.swap_remove(idx);
+        let mut rng = rng();
+        for &placement in dissolved.placements() {
+            let rect = placement.rect;
+            // Try boxes in random order, rectangles which fit nowhere get a new box
+            let mut order: Vec<usize> = (0..new_sol.boxes.len()).collect();
+            order.shuffle(&mut rng);
+            let target = order.into_iter().find_map(|i| {
+                new_sol.find_position(i, rect).map(|pos| (i, pos))
+            });
+            match target {
+                Some((i, (x, y, rotated))) => new_sol.boxes[i].place(Placement { rect, x, y, rotated }),
+                None => new_sol.open_box(rect),
+            }
+        }
+        new_sol
+    }
+}
+
+// ---------------------------------------------------------
+// Perturbations for PermutationSolution
+// ---------------------------------------------------------
+
+/// Shuffle a random segment of the sequence with at most max_len elements
+pub struct SegmentShuffle {
+    pub max_len: usize,
+}
+
+impl Perturbation<PermutationSolution> for SegmentShuffle {
+    fn perturb(&self, solution: &PermutationSolution) -> PermutationSolution {
+        let mut new_sol = solution.clone();
+        let n = new_sol.sequence.len();
+        if n < 2 || self.max_len < 2 {
+            return new_sol;
+        }
+        let mut rng = rng();
+        let len = rng.random_range(2..=self.max_len.min(n));
+        let start = rng.random_range(0..=n - len);
+        new_sol.sequence[start..start + len].shuffle(&mut rng);
+        new_sol
+    }
+}
+
+/// Cut the sequence into A B C D and reconnect as A C B D
+pub struct DoubleBridge;
+
+impl Perturbation<PermutationSolution> for DoubleBridge {
+    fn perturb(&self, solution: &PermutationSolution) -> PermutationSolution {
+        let mut new_sol = solution.clone();
+        let n = new_sol.sequence.len();
+        if n < 4 {
+            return new_sol;
+        }
+        let mut rng = rng();
+        // Three cut points 0 < a < b < c < n
+        let mut cuts = rand::seq::index::sample(&mut rng, n - 1, 3).into_vec();
+        cuts.iter_mut().for_each(|c| *c += 1);
+        cuts.sort_unstable();
+        let (a, b, c) = (cuts[0], cuts[1], cuts[2]);
+
+        let seq = &solution.sequence;
+        new_sol.sequence = seq[..a].iter()
+            .chain(&seq[b..c])
+            .chain(&seq[a..b])
+            .chain(&seq[c..])
+            .copied()
+            .collect();
+        new_sol
+    }
+}
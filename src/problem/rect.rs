@@ -1,16 +1,79 @@
+/// Allowed orientations of a rectangle
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    // Both orientations
+    #[default]
+    Free,
+    // Only unrotated, e.g. printed or grained material
+    Fixed,
+    // Only rotated by 90 degrees
+    Rotated,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Copy)]
 pub struct Rect {
     pub id: usize,
     pub width: u32,
     pub height: u32,
+    pub rotation: Rotation,
 }
 
 impl Rect {
     pub fn new(id: usize, width: u32, height: u32) -> Self {
-        Self { id, width, height }        
+        Self { id, width, height, rotation: Rotation::Free }        
+    }
+
+    // Restrict the allowed orientations
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
     }
 
     pub fn area(&self) -> u32 {
         self.width * self.height
     }
+
+    /// Allowed values of the rotated flag of a placement, unrotated first
+    pub fn orientations(&self) -> &'static [bool] {
+        match self.rotation {
+            Rotation::Free => &[false, true],
+            Rotation::Fixed => &[false],
+            Rotation::Rotated => &[true],
+        }
+    }
+
+    pub fn allows(&self, rotated: bool) -> bool {
+        self.orientations().contains(&rotated)
+    }
+
+    /// Same dimensions and rotation permission, only the id differs
+    pub fn is_identical(&self, other: &Rect) -> bool {
+        (self.width, self.height, self.rotation) == (other.width, other.height, other.rotation)
+    }
+}
+
+/// Rectangle type of an order, demand is the number of pieces
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RectType {
+    pub width: u32,
+    pub height: u32,
+    pub rotation: Rotation,
+    pub demand: usize,
+}
+
+impl RectType {
+    pub fn new(width: u32, height: u32, demand: usize) -> Self {
+        Self { width, height, rotation: Rotation::Free, demand }
+    }
+
+    // Restrict the allowed orientations of all pieces
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Piece of this type with the given id
+    pub fn piece(&self, id: usize) -> Rect {
+        Rect::new(id, self.width, self.height).with_rotation(self.rotation)
+    }
 }
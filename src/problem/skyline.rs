@@ -0,0 +1,191 @@
+use super::maxrects::{FreeRect, MaxRects};
+use super::rect::Rect;
+use super::solution::{BoxBin, Placement};
+
+/// Horizontal part of the skyline, covers [x, x + width) at height y
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub x: u32,
+    pub width: u32,
+    pub y: u32,
+}
+
+/// Top contour of the placements of a box. Gaps below the contour are kept in the waste map,
+/// so placing a rectangle needs no intersection test against other placements
+#[derive(Clone, Debug)]
+pub struct Skyline {
+    pub width: u32,
+    pub height: u32,
+    // Sorted by x, covering [0, width) without gaps
+    pub segments: Vec<Segment>,
+    // Free rectangles below the skyline
+    pub waste: MaxRects,
+}
+
+impl Skyline {
+    // Empty box
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, segments: vec![Segment { x: 0, width, y: 0 }], waste: MaxRects::full(width, height) }
+    }
+
+    /// Skyline of a box with its current placements, they are added from bottom to top
+    pub fn from_bin(bin: &BoxBin) -> Self {
+        let mut skyline = Self::new(bin.width, bin.height);
+        let mut placements: Vec<&Placement> = bin.placements().iter().collect();
+        placements.sort_by_key(|p| (p.y, p.x));
+        for placement in placements {
+            skyline.place(placement);
+        }
+        skyline
+    }
+
+    /// Raise the skyline above the placement, the space between old skyline and placement becomes waste
+    pub fn place(&mut self, placement: &Placement) {
+        let (x, x2) = (placement.x, placement.x + placement.width());
+        let (y, top) = (placement.y, placement.y + placement.height());
+        // Placement may use a gap of the waste map
+        self.waste.place(placement);
+        self.split_at(x);
+        self.split_at(x2);
+        for segment in self.segments.iter_mut().filter(|s| s.x >= x && s.x + s.width <= x2) {
+            if segment.y < y {
+                self.waste.add_free(FreeRect { x: segment.x, y: segment.y, width: segment.width, height: y - segment.y });
+            }
+            segment.y = segment.y.max(top);
+        }
+        // Merge neighboring segments of equal height
+        self.segments.dedup_by(|next, prev| {
+            if prev.y == next.y {
+                prev.width += next.width;
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    // Split the segment containing x, so a segment starts at x
+    fn split_at(&mut self, x: u32) {
+        if let Some(idx) = self.segments.iter().position(|s| s.x < x && x < s.x + s.width) {
+            let segment = self.segments[idx];
+            self.segments[idx].width = x - segment.x;
+            self.segments.insert(idx + 1, Segment { x, width: segment.x + segment.width - x, y: segment.y });
+        }
+    }
+
+    /// Gaps of the waste map are used first (best short side fit),
+    /// otherwise the lowest position on the skyline, leftmost on ties
+    pub fn find_position(&self, rect: Rect, orientations: &[bool]) -> Option<(u32, u32, bool)> {
+        let waste_fit = self.waste.best_fit(rect, orientations, |f, w, h| {
+            let (left_w, left_h) = ((f.width - w) as i64, (f.height - h) as i64);
+            (left_w.min(left_h), left_w.max(left_h))
+        });
+        if waste_fit.is_some() {
+            return waste_fit;
+        }
+        let mut best: Option<(u32, u32, bool)> = None;
+        for (idx, segment) in self.segments.iter().enumerate() {
+            for &rotated in orientations {
+                let (w, h) = if rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
+                let x = segment.x;
+                if x + w > self.width {
+                    continue;
+                }
+                // Rect rests on the highest segment below it
+                let y = self.segments[idx..].iter()
+                    .take_while(|s| s.x < x + w)
+                    .map(|s| s.y)
+                    .max()
+                    .unwrap_or(0);
+                if y + h > self.height {
+                    continue;
+                }
+                if best.is_none_or(|(bx, by, _)| (y, x) < (by, bx)) {
+                    best = Some((x, y, rotated));
+                }
+            }
+        }
+        best
+    }
+
+    /// Left ends of the segments and corners of the waste map, sorted by y then x
+    pub fn corners(&self) -> Vec<(u32, u32)> {
+        let mut corners: Vec<(u32, u32)> = self.segments.iter()
+            .filter(|s| s.y < self.height)
+            .map(|s| (s.x, s.y))
+            .chain(self.waste.corners())
+            .collect();
+        corners.sort_by_key(|&(x, y)| (y, x));
+        corners.dedup();
+        corners
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+    use crate::problem::solution::PlacementRule;
+
+    // Segments cover the box width without gaps, no placement reaches above the skyline
+    // and no waste rect overlaps a placement
+    fn assert_consistent(skyline: &Skyline, bin: &BoxBin) {
+        let mut x = 0;
+        for segment in &skyline.segments {
+            assert_eq!(segment.x, x);
+            x += segment.width;
+        }
+        assert_eq!(x, bin.width);
+        for p in bin.placements() {
+            let covering = skyline.segments.iter().filter(|s| s.x < p.x + p.width() && p.x < s.x + s.width);
+            assert!(covering.into_iter().all(|s| s.y >= p.y + p.height()));
+        }
+        for f in &skyline.waste.free {
+            let free = Placement { rect: Rect::new(usize::MAX, f.width, f.height), x: f.x, y: f.y, rotated: false };
+            assert!(bin.placements().iter().all(|p| p.intersection_area(&free) == 0));
+        }
+    }
+
+    #[test]
+    fn placements_are_in_bounds_and_disjoint() {
+        let rects = Generator::generate_instance(40, (3, 15), (3, 15), 40, 30).rects;
+        let mut bin = BoxBin::new(40, 30);
+        let mut skyline = Skyline::new(40, 30);
+        for &rect in &rects {
+            let Some((x, y, rotated)) = skyline.find_position(rect, rect.orientations()) else { continue };
+            let placement = Placement { rect, x, y, rotated };
+            assert!(x + placement.width() <= bin.width && y + placement.height() <= bin.height);
+            assert!(bin.placements().iter().all(|p| p.intersection_area(&placement) == 0));
+            skyline.place(&placement);
+            bin.place(placement);
+            assert_consistent(&skyline, &bin);
+        }
+        assert!(bin.placements().len() > 1);
+    }
+
+    #[test]
+    fn skyline_follows_placements() {
+        let rects = Generator::generate_instance(40, (3, 15), (3, 15), 40, 30).rects;
+        let mut bin = BoxBin::new(40, 30);
+        let mut skyline = Skyline::new(40, 30);
+        for &rect in &rects {
+            // Skyline cached by the box gives the same position as the one updated here
+            let position = bin.find_position_with(rect, rect.orientations(), PlacementRule::Skyline);
+            assert_eq!(position, skyline.find_position(rect, rect.orientations()));
+            let Some((x, y, rotated)) = position else { continue };
+            let placement = Placement { rect, x, y, rotated };
+            let before = skyline.segments.clone();
+            skyline.place(&placement);
+            bin.place(placement);
+            assert_consistent(&skyline, &bin);
+            // Placements on the skyline raise it to their top, placements in a gap below keep it
+            let (x2, top) = (x + placement.width(), y + placement.height());
+            let under = |segments: &[Segment]| segments.iter().filter(|s| s.x < x2 && x < s.x + s.width).map(|s| s.y).collect::<Vec<_>>();
+            if under(&before).iter().all(|&h| h <= y) {
+                assert!(under(&skyline.segments).iter().all(|&h| h == top));
+            } else {
+                assert_eq!(skyline.segments, before);
+            }
+        }
+    }
+}
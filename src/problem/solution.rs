@@ -1,7 +1,95 @@
 use super::rect::Rect;
-use super::instance::Instance;
+use super::instance::{BoxType, Instance};
+use super::maxrects::{MaxRects, MaxRectsHeuristic};
+use super::guillotine::{is_guillotine, GuillotineFreeRects, GuillotineSplit};
+use super::skyline::Skyline;
 use crate::algorithms::traits::Solution;
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// How positions for a rectangle are searched in a box.
+/// In guillotine mode only Guillotine keeps its own scoring. The other rules only supply their candidate
+/// positions, sorted by y then x, and the first one inside a guillotine free rectangle of the default split
+/// is taken, so the MaxRects heuristic and the lowest skyline position are not used
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlacementRule {
+    // Origin and the right-bottom and left-top corners of all placements, bottom-left first
+    #[default]
+    CandidatePoints,
+    // Maximal free rectangles scored by the heuristic
+    MaxRects(MaxRectsHeuristic),
+    // Lowest position on the top contour, gaps below are reused through a waste map
+    Skyline,
+    // Disjoint free rectangles of the guillotine cuts, split by the rule after each placement
+    Guillotine(GuillotineSplit),
+}
+
+impl fmt::Display for PlacementRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlacementRule::CandidatePoints => write!(f, "Candidate Points"),
+            PlacementRule::MaxRects(heuristic) => write!(f, "MaxRects {}", heuristic),
+            PlacementRule::Skyline => write!(f, "Skyline"),
+            PlacementRule::Guillotine(split) => write!(f, "Guillotine {}", split),
+        }
+    }
+}
+
+/// Which box type is opened for a rectangle which fits in no open box
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BoxSelection {
+    // Lowest cost per area, larger box on ties
+    #[default]
+    CostPerArea,
+    // Lowest cost, larger box on ties
+    Cheapest,
+    // Largest area, lower cost on ties
+    Largest,
+}
+
+impl fmt::Display for BoxSelection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoxSelection::CostPerArea => write!(f, "Cost per Area"),
+            BoxSelection::Cheapest => write!(f, "Cheapest"),
+            BoxSelection::Largest => write!(f, "Largest"),
+        }
+    }
+}
+
+impl BoxSelection {
+    /// Box type and orientation for a new box holding rect. Only types with boxes left
+    /// and room for rect in one of the orientations are considered
+    pub fn select(&self, instance: &Instance, boxes: &[BoxBin], rect: Rect, orientations: &[bool]) -> Option<(usize, bool)> {
+        let mut opened = vec![0; instance.box_types.len()];
+        for bin in boxes {
+            opened[bin.box_type] += 1;
+        }
+        self.best(instance, rect, orientations, |t| instance.box_types[t].available.is_none_or(|n| opened[t] < n))
+    }
+
+    // Best type of the selection among the available types, unrotated first
+    fn best(&self, instance: &Instance, rect: Rect, orientations: &[bool], available: impl Fn(usize) -> bool) -> Option<(usize, bool)> {
+        instance.box_types.iter().enumerate()
+            .filter(|&(t, _)| available(t))
+            .filter_map(|(t, box_type)| orientations.iter().find(|&&r| box_type.fits(rect, r)).map(|&r| (t, r)))
+            .min_by(|&(a, _), &(b, _)| self.compare(&instance.box_types[a], &instance.box_types[b]))
+    }
+
+    fn compare(&self, a: &BoxType, b: &BoxType) -> Ordering {
+        match self {
+            // a.cost / a.area < b.cost / b.area without division
+            BoxSelection::CostPerArea => (a.cost as u128 * b.area() as u128).cmp(&(b.cost as u128 * a.area() as u128))
+                .then(b.area().cmp(&a.area())),
+            BoxSelection::Cheapest => a.cost.cmp(&b.cost).then(b.area().cmp(&a.area())),
+            BoxSelection::Largest => b.area().cmp(&a.area()).then(a.cost.cmp(&b.cost)),
+        }
+    }
+}
 
 
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
@@ -30,8 +118,7 @@ impl Placement {
         let r2_x2 = other.x + other.width();    // Right border
         let r2_y2 = other.y + other.height();   // Top border
         // Check if one Placement is left, right, top or down of other Placement -> no intersect
-        let intersects = !(r1_x2 <= other.x || r2_x2 <= self.x || r1_y2 <= other.y || r2_y2 <= self.y);
-        intersects
+        !(r1_x2 <= other.x || r2_x2 <= self.x || r1_y2 <= other.y || r2_y2 <= self.y)
     }
 
     pub fn intersection_area(&self, other: &Placement) -> u32 {
@@ -50,21 +137,89 @@ impl Placement {
     }
 }
 
+/// Free space of a box for the placement rules, built on first use and updated with every placement.
+/// Removing a placement discards it, it is built again on the next use
+#[derive(Clone, Debug, Default)]
+struct FreeSpace {
+    max_rects: OnceCell<MaxRects>,
+    skyline: OnceCell<Skyline>,
+    // None if the box is not guillotine cuttable
+    guillotine: OnceCell<Option<GuillotineFreeRects>>,
+}
+
+impl FreeSpace {
+    fn place(&mut self, placement: &Placement) {
+        if let Some(max_rects) = self.max_rects.get_mut() {
+            max_rects.place(placement);
+        }
+        if let Some(skyline) = self.skyline.get_mut() {
+            skyline.place(placement);
+        }
+        // Placements outside the guillotine free rectangles need a new cut decomposition
+        if let Some(Some(guillotine)) = self.guillotine.get_mut() {
+            if !guillotine.place(placement) {
+                self.guillotine.take();
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BoxBin {
-    pub capacity: u32,  // Denotes box length L
-    pub placements: Vec<Placement>
+    // Stable identity in a solution, kept by clones and while other boxes are removed. Set by the solution the box is added to
+    pub id: usize,
+    // Box dimensions W x H
+    pub width: u32,
+    pub height: u32,
+    // Index in the box types of the instance
+    pub box_type: usize,
+    // Only changed through place and remove, so the free space stays up to date
+    placements: Vec<Placement>,
+    free_space: FreeSpace,
 }
 
 impl BoxBin {
-    pub fn new(capacity: u32) -> Self {
-        Self { capacity, placements: Vec::new() }
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { id: 0, width, height, box_type: 0, placements: Vec::new(), free_space: FreeSpace::default() }
+    }
+
+    pub fn placements(&self) -> &[Placement] {
+        &self.placements
+    }
+
+    /// Add a placement without any check
+    pub fn place(&mut self, placement: Placement) {
+        self.free_space.place(&placement);
+        self.placements.push(placement);
+    }
+
+    /// Remove the placement with the given index, the last placement takes its index
+    pub fn remove(&mut self, p_idx: usize) -> Placement {
+        self.free_space.clear();
+        self.placements.swap_remove(p_idx)
+    }
+
+    /// Revert remove, the placement gets its index back
+    pub fn restore(&mut self, p_idx: usize, placement: Placement) {
+        self.place(placement);
+        let last = self.placements.len() - 1;
+        self.placements.swap(p_idx, last);
+    }
+
+    /// Remove the last placement
+    pub fn pop(&mut self) -> Option<Placement> {
+        self.free_space.clear();
+        self.placements.pop()
     }
 
     pub fn try_place(&mut self, rect: Rect, x: u32, y: u32, rotated: bool) -> bool {
         let new_placement = Placement { rect, x, y, rotated };
         // Check box bounds
-        if x + new_placement.width() > self.capacity || y + new_placement.height() > self.capacity {
+        if x + new_placement.width() > self.width || y + new_placement.height() > self.height {
             return false;
         }
         // Check collision with other placements
@@ -74,37 +229,115 @@ impl BoxBin {
             }
         }
 
-        self.placements.push(new_placement);
+        self.place(new_placement);
         true
     }
 
+    // Maximal free rectangles, kept up to date by place
+    fn max_rects(&self) -> &MaxRects {
+        self.free_space.max_rects.get_or_init(|| MaxRects::from_bin(self))
+    }
+
+    // Skyline with waste map, kept up to date by place
+    fn skyline(&self) -> &Skyline {
+        self.free_space.skyline.get_or_init(|| Skyline::from_bin(self))
+    }
+
+    // Guillotine free rectangles of the split rule, kept up to date by place for the split rule of the first use.
+    // None if the box is not guillotine cuttable
+    fn guillotine_free_rects(&self, split: GuillotineSplit) -> Option<Cow<'_, GuillotineFreeRects>> {
+        match self.free_space.guillotine.get_or_init(|| GuillotineFreeRects::from_bin(self, split)) {
+            Some(free_rects) if free_rects.split == split => Some(Cow::Borrowed(free_rects)),
+            Some(_) => GuillotineFreeRects::from_bin(self, split).map(Cow::Owned),
+            None => None,
+        }
+    }
+
     pub fn find_position_in_box(&self, rect: Rect) -> Option<(u32, u32, bool)> {
-        // Collect candidates (origin + edges of existing rectangles)
-        let mut candidates = HashSet::new();
-        candidates.insert((0, 0));
-
-        for p in &self.placements {
-            let c1 = (p.x + p.width(), p.y);    // Right bottom of p
-            let c2 = (p.x, p.y + p.height());   // Left top of p
-            
-            if c1.0 < self.capacity && c1.1 < self.capacity { candidates.insert(c1); }
-            if c2.0 < self.capacity && c2.1 < self.capacity { candidates.insert(c2); }
-        }
-        // Sort candidates by bottom-left heuristic
-        let mut sorted_candidates: Vec<(u32, u32)> = candidates.into_iter().collect();
-        sorted_candidates.sort_by(|a, b| {
-            if a.1 != b.1 { 
-                a.1.cmp(&b.1) 
-            } else {
-                a.0.cmp(&b.0)
+        self.find_position(rect, rect.orientations())
+    }
+
+    /// Search position with the given orientations, tried in order at every candidate
+    pub fn find_position(&self, rect: Rect, orientations: &[bool]) -> Option<(u32, u32, bool)> {
+        self.find_position_with(rect, orientations, PlacementRule::CandidatePoints)
+    }
+
+    /// Search position with the placement rule
+    pub fn find_position_with(&self, rect: Rect, orientations: &[bool], rule: PlacementRule) -> Option<(u32, u32, bool)> {
+        match rule {
+            PlacementRule::CandidatePoints => {
+                // Check candidates
+                for (x, y) in self.candidate_positions(rule) {
+                    for &rotated in orientations {
+                        if self.can_place(rect, x, y, rotated) { return Some((x, y, rotated)); }
+                    }
+                }
+                None
+            }
+            PlacementRule::MaxRects(heuristic) => self.max_rects().find_position(self, rect, orientations, heuristic),
+            PlacementRule::Skyline => self.skyline().find_position(rect, orientations),
+            PlacementRule::Guillotine(split) => self.guillotine_free_rects(split)?.find_position(rect, orientations),
+        }
+    }
+
+    /// Search position which keeps the box guillotine cuttable. Other rules than Guillotine try their candidate
+    /// positions in order and take the first one inside a guillotine free rectangle of the default split rule
+    pub fn find_guillotine_position(&self, rect: Rect, orientations: &[bool], rule: PlacementRule) -> Option<(u32, u32, bool)> {
+        if let PlacementRule::Guillotine(_) = rule {
+            return self.find_position_with(rect, orientations, rule);
+        }
+        let free_rects = self.guillotine_free_rects(GuillotineSplit::default())?;
+        for (x, y) in self.candidate_positions(rule) {
+            for &rotated in orientations {
+                let (w, h) = if rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
+                if free_rects.holds(x, y, w, h) {
+                    return Some((x, y, rotated));
+                }
+            }
+        }
+        None
+    }
+
+    /// True if the box can be cut edge to edge until every part holds at most one rectangle
+    pub fn is_guillotine(&self) -> bool {
+        is_guillotine(self)
+    }
+
+    /// Candidate positions of the placement rule, sorted by y then x
+    pub fn candidate_positions(&self, rule: PlacementRule) -> Vec<(u32, u32)> {
+        match rule {
+            PlacementRule::CandidatePoints => {
+                // Collect candidates (origin + edges of existing rectangles)
+                let mut candidates = HashSet::new();
+                candidates.insert((0, 0));
+
+                for p in &self.placements {
+                    let c1 = (p.x + p.width(), p.y);    // Right bottom of p
+                    let c2 = (p.x, p.y + p.height());   // Left top of p
+                    
+                    if c1.0 < self.width && c1.1 < self.height { candidates.insert(c1); }
+                    if c2.0 < self.width && c2.1 < self.height { candidates.insert(c2); }
+                }
+                // Sort candidates by bottom-left heuristic
+                let mut sorted_candidates: Vec<(u32, u32)> = candidates.into_iter().collect();
+                sorted_candidates.sort_by(|a, b| {
+                    if a.1 != b.1 { 
+                        a.1.cmp(&b.1) 
+                    } else {
+                        a.0.cmp(&b.0)
+                    }
+                });
+                sorted_candidates
             }
-        });
-        // Check candidates
-        for (x, y) in sorted_candidates {
-            if self.can_place(rect, x, y, false) { return Some((x, y, false)); } 
-            if self.can_place(rect, x, y, true) { return Some((x, y, true)); }
+            PlacementRule::MaxRects(_) => self.max_rects().corners(),
+            PlacementRule::Skyline => self.skyline().corners(),
+            PlacementRule::Guillotine(split) => self.guillotine_free_rects(split).map(|g| g.corners()).unwrap_or_default(),
         }
-        None    
+    }
+
+    // Sum of the areas of all placed rectangles
+    pub fn used_area(&self) -> u32 {
+        self.placements.iter().map(|p| p.rect.area()).sum()
     }
 
     // Checks if rectangles on position x, y can placed correctly
@@ -112,13 +345,13 @@ impl BoxBin {
         let w = if rotated { rect.height } else { rect.width };
         let h = if rotated { rect.width } else { rect.height };
         // Boundary check
-        if x + w > self.capacity || y + h > self.capacity {
+        if x + w > self.width || y + h > self.height {
             return false;
         }
         // Intersection check
         let candidate = Placement { rect, x, y, rotated };
         for existing in &self.placements {
-            if candidate.intersects(&existing) {
+            if candidate.intersects(existing) {
                 return false;
             }
         }
@@ -130,30 +363,91 @@ impl BoxBin {
 /// and Local Search with overlaping
 #[derive(Clone, Debug)]
 pub struct RectangleSolution {
-    pub instance: Instance,
+    // Shared between all solutions, the instance never changes during a search
+    pub instance: Arc<Instance>,
     pub boxes: Vec<BoxBin>,
     // Penalty for overlaping mode
     pub penalty_factor: Option<i64>,
+    // Position search used by greedy, neighborhoods and repair operators
+    pub placement: PlacementRule,
+    // Type of the boxes opened by greedy, neighborhoods and repair operators
+    pub box_selection: BoxSelection,
+    // Id of the next added box, ids are never used twice in a solution and the solutions derived from it
+    next_box_id: usize,
 }
 
 impl RectangleSolution {
     // Standard constructor
-    pub fn new(instance: Instance) -> Self {
-        Self { instance, boxes: Vec::new(), penalty_factor: None }
+    pub fn new(instance: Arc<Instance>) -> Self {
+        Self { instance, boxes: Vec::new(), penalty_factor: None, placement: PlacementRule::default(), box_selection: BoxSelection::default(), next_box_id: 0 }
     }
     // Constructor for overlaping mode
     pub fn with_penalty(mut self,factor: i64) -> Self {
         self.penalty_factor = Some(factor);
         self
     }
+    // Search positions with another placement rule
+    pub fn with_placement(mut self, rule: PlacementRule) -> Self {
+        self.placement = rule;
+        self
+    }
+    // Open boxes with another box type selection
+    pub fn with_box_selection(mut self, selection: BoxSelection) -> Self {
+        self.box_selection = selection;
+        self
+    }
+    /// Position for rect in the box with the placement rule of the solution, honours guillotine mode
+    pub fn find_position(&self, box_idx: usize, rect: Rect) -> Option<(u32, u32, bool)> {
+        find_position_in(&self.instance, &self.boxes[box_idx], rect, rect.orientations(), self.placement)
+    }
+    /// Box type and orientation of a new box for rect, None if no type with room for rect has boxes left
+    pub fn new_box_type(&self, rect: Rect) -> Option<(usize, bool)> {
+        self.box_selection.select(&self.instance, &self.boxes, rect, rect.orientations())
+    }
+    /// Open a new box with rect in its left-bottom corner
+    pub fn open_box(&mut self, rect: Rect) {
+        open_box(&self.instance, &mut self.boxes, rect, rect.orientations(), self.box_selection);
+        let id = self.next_id();
+        if let Some(bin) = self.boxes.last_mut() {
+            bin.id = id;
+        }
+    }
+    /// Add a box with a new id, returns the id
+    pub fn add_box(&mut self, mut bin: BoxBin) -> usize {
+        let id = self.next_id();
+        bin.id = id;
+        self.boxes.push(bin);
+        id
+    }
+    /// Replace all boxes, they get new ids in order
+    pub fn set_boxes(&mut self, boxes: Vec<BoxBin>) {
+        self.boxes = boxes;
+        for (id, bin) in self.boxes.iter_mut().enumerate() {
+            bin.id = id;
+        }
+        self.next_box_id = self.boxes.len();
+    }
+    fn next_id(&mut self) -> usize {
+        self.next_box_id += 1;
+        self.next_box_id - 1
+    }
+    // Total cost of the boxes
+    pub fn box_cost(&self) -> usize {
+        box_cost(&self.instance, &self.boxes)
+    }
+    // Dynamic Weighting for overlaping mode: Weight must be > max possible density score ((W*H)^2)
+    // to ensure box reduction is prioritized over density.
+    pub fn box_weight(&self) -> i64 {
+        (self.instance.box_area() as i64).pow(2) + 1
+    }
 }
 
 impl Solution for RectangleSolution {
-    // Number of boxes and score as cost
+    // Total box cost and score as cost, the number of boxes for unit costs
     type Cost = (usize, i64);
 
     fn cost(&self) -> Self::Cost {
-        let num_boxes = self.boxes.len();
+        let total_cost = self.box_cost();
         // Score: sum of squares of used area in each box
         let mut score: i64 = 0;
         // Case distinction for overlaping and standard cost calculation
@@ -174,21 +468,16 @@ impl Solution for RectangleSolution {
                 }
                 total_penalty += bin_penalty;
                 // Negative density score
-                let used_area: u32 = bin.placements.iter().map(|p| p.rect.area()).sum();
-                density_score -= (used_area as i64).pow(2);
+                density_score -= (bin.used_area() as i64).pow(2);
             }
-            // Dynamic Weighting: Weight must be > max possible density score (L^4)
-            // to ensure box reduction is prioritized over density.
-            let box_weight = (self.instance.box_size as i64).pow(4) + 1;
-            score = total_penalty * penalty_factor + density_score + (num_boxes as i64) * box_weight;
+            score = total_penalty * penalty_factor + density_score + (total_cost as i64) * self.box_weight();
 
             (0, score)
         } else {
             for b in &self.boxes {
-                let used_area: u32 = b.placements.iter().map(|p| p.rect.area()).sum();
-                score -= (used_area as i64).pow(2);
+                score -= (b.used_area() as i64).pow(2);
             }
-            (num_boxes, score)
+            (total_cost, score)
         }
 
     }
@@ -197,46 +486,154 @@ impl Solution for RectangleSolution {
 /// Solution for rule based Local Search
 #[derive(Clone, Debug)]
 pub struct PermutationSolution {
-    pub instance: Instance,
+    pub instance: Arc<Instance>,
     pub sequence: Vec<Rect>,
+    // Position search and box type selection of the decoder
+    pub placement: PlacementRule,
+    pub box_selection: BoxSelection,
 }
 
 impl PermutationSolution {
-    pub fn new(instance: Instance, sequence: Vec<Rect>) -> Self {
-        Self { instance, sequence }
+    pub fn new(instance: Arc<Instance>, sequence: Vec<Rect>) -> Self {
+        Self { instance, sequence, placement: PlacementRule::default(), box_selection: BoxSelection::default() }
+    }
+
+    pub fn with_placement(mut self, rule: PlacementRule) -> Self {
+        self.placement = rule;
+        self
+    }
+
+    pub fn with_box_selection(mut self, selection: BoxSelection) -> Self {
+        self.box_selection = selection;
+        self
+    }
+
+    /// Decode a rectangle sequence into boxes with first fit and calculate its cost
+    pub fn decode_cost(&self, sequence: impl Iterator<Item = Rect>) -> (usize, i64) {
+        self.decode_cost_from(Vec::new(), sequence)
+    }
+
+    /// Boxes of the first len rectangles of the sequence
+    pub fn decode_prefix(&self, len: usize) -> Vec<BoxBin> {
+        first_fit(&self.instance, self.placement, self.box_selection, self.sequence[..len].iter().map(|&rect| (rect, None)))
+    }
+
+    /// Continue first fit from the boxes of a sequence prefix and calculate the cost of all boxes
+    pub fn decode_cost_from(&self, prefix: Vec<BoxBin>, sequence: impl Iterator<Item = Rect>) -> (usize, i64) {
+        let boxes = first_fit_from(&self.instance, self.placement, self.box_selection, prefix, sequence.map(|rect| (rect, None)));
+        packing_cost(&self.instance, &boxes)
     }
 }
 
-impl Solution for PermutationSolution {
-    type Cost = (usize, i64);
+/// Pack rectangles in sequence order into the first box they fit,
+/// orientation None or an orientation the rect doesn't allow tries all allowed orientations
+pub fn first_fit(instance: &Instance, rule: PlacementRule, selection: BoxSelection, sequence: impl Iterator<Item = (Rect, Option<bool>)>) -> Vec<BoxBin> {
+    first_fit_from(instance, rule, selection, Vec::new(), sequence)
+}
 
-    fn cost(&self) -> Self::Cost {
-        let mut boxes: Vec<BoxBin> = Vec::new();
-        let box_size = self.instance.box_size;
-
-        for &rect in &self.sequence {
-            let mut placed = false;
-            
-            for bin in boxes.iter_mut() {
-                if let Some((x, y, rotated)) = bin.find_position_in_box(rect) {
-                    bin.placements.push(Placement { rect, x, y, rotated });
-                    placed = true;
-                    break;
-                }
-            }
-            if !placed {
-                let mut new_bin = BoxBin::new(box_size);
-                new_bin.placements.push(Placement { rect, x: 0, y: 0, rotated: false });
-                boxes.push(new_bin);
+/// First fit continued from already packed boxes
+pub fn first_fit_from(instance: &Instance, rule: PlacementRule, selection: BoxSelection, mut boxes: Vec<BoxBin>,
+                      sequence: impl Iterator<Item = (Rect, Option<bool>)>) -> Vec<BoxBin> {
+    // Previous rect, its orientations and box. An identical rect doesn't fit in the boxes before
+    let mut previous: Option<(Rect, &[bool], usize)> = None;
+
+    for (rect, orientation) in sequence {
+        let orientations: &[bool] = match orientation {
+            Some(false) if rect.allows(false) => &[false],
+            Some(true) if rect.allows(true) => &[true],
+            _ => rect.orientations(),
+        };
+        let first_box = match previous {
+            Some((prev, prev_orientations, idx)) if prev.is_identical(&rect) && prev_orientations == orientations => idx,
+            _ => 0,
+        };
+        let mut placed = false;
+        
+        for (idx, bin) in boxes.iter_mut().enumerate().skip(first_box) {
+            if let Some((x, y, rotated)) = find_position_in(instance, bin, rect, orientations, rule) {
+                bin.place(Placement { rect, x, y, rotated });
+                previous = Some((rect, orientations, idx));
+                placed = true;
+                break;
             }
         }
-        let num_boxes = boxes.len();
-        let mut score: i64 = 0;
-        for b in &boxes {
-            let used_area: u32 = b.placements.iter().map(|p| p.rect.area()).sum();
-            score += (used_area as i64).pow(2);
+        if !placed {
+            open_box(instance, &mut boxes, rect, orientations, selection);
+            previous = Some((rect, orientations, boxes.len() - 1));
         }
-        (num_boxes, -score)
+    }
+    boxes
+}
+
+/// Open a box of the type chosen by the selection with rect in its left-bottom corner.
+/// Types with boxes left are preferred, orientations are relaxed to the allowed ones first, then the availability
+pub fn open_box(instance: &Instance, boxes: &mut Vec<BoxBin>, rect: Rect, orientations: &[bool], selection: BoxSelection) -> Placement {
+    let (box_type, rotated) = selection.select(instance, boxes, rect, orientations)
+        .or_else(|| selection.select(instance, boxes, rect, rect.orientations()))
+        .or_else(|| selection.best(instance, rect, rect.orientations(), |_| true))
+        .unwrap_or_else(|| panic!("Rectangle {} fits in no box type", rect.id));
+    let placement = Placement { rect, x: 0, y: 0, rotated };
+    let mut new_bin = instance.new_box(box_type);
+    new_bin.place(placement);
+    boxes.push(new_bin);
+    placement
+}
+
+/// Position search of the rule, in guillotine mode of the instance only positions keeping the box guillotine cuttable
+pub fn find_position_in(instance: &Instance, bin: &BoxBin, rect: Rect, orientations: &[bool], rule: PlacementRule) -> Option<(u32, u32, bool)> {
+    if instance.guillotine {
+        bin.find_guillotine_position(rect, orientations, rule)
+    } else {
+        bin.find_position_with(rect, orientations, rule)
+    }
+}
+
+/// Total cost of the box types
+pub fn box_cost(instance: &Instance, boxes: &[BoxBin]) -> usize {
+    boxes.iter().map(|b| instance.box_types[b.box_type].cost).sum()
+}
+
+/// Total box cost and negative sum of squares of used area in each box
+pub fn packing_cost(instance: &Instance, boxes: &[BoxBin]) -> (usize, i64) {
+    let mut score: i64 = 0;
+    for b in boxes {
+        score += (b.used_area() as i64).pow(2);
+    }
+    (box_cost(instance, boxes), -score)
+}
+
+impl Solution for PermutationSolution {
+    type Cost = (usize, i64);
+
+    fn cost(&self) -> Self::Cost {
+        self.decode_cost(self.sequence.iter().copied())
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn box_ids_are_counted_per_solution() {
+        let instance = Arc::new(Instance::new(10, 10, (0..4).map(|id| Rect::new(id, 10, 10)).collect()));
+        let mut solution = RectangleSolution::new(Arc::clone(&instance));
+        for _ in 0..3 {
+            solution.add_box(BoxBin::new(10, 10));
+        }
+        let ids = |solution: &RectangleSolution| solution.boxes.iter().map(|b| b.id).collect::<Vec<_>>();
+        assert_eq!(ids(&solution), [0, 1, 2]);
+
+        // Removed ids are not used again, clones count on from the same id
+        solution.boxes.swap_remove(0);
+        let mut other = solution.clone();
+        solution.add_box(BoxBin::new(10, 10));
+        other.add_box(BoxBin::new(10, 10));
+        assert_eq!(ids(&solution), [2, 1, 3]);
+        assert_eq!(ids(&other), [2, 1, 3]);
+
+        solution.set_boxes(first_fit(&instance, PlacementRule::default(), BoxSelection::default(), instance.rects.iter().map(|&r| (r, None))));
+        assert_eq!(ids(&solution), [0, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,162 @@
+use super::solution::RectangleSolution;
+use std::fmt;
+
+/// First violated constraint of a solution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    // Rect exceeds the border of its box
+    OutOfBounds { box_idx: usize, rect_id: usize },
+    Overlap { box_idx: usize, first: usize, second: usize },
+    // Rect of the instance is not placed
+    MissingRect(usize),
+    // Rect is placed more than once
+    DuplicateRect(usize),
+    // Placed rect is not part of the instance
+    UnknownRect(usize),
+    // Rect is placed in an orientation its rotation permission forbids
+    RotationNotAllowed(usize),
+    // Box can't be cut with guillotine cuts, only checked in guillotine mode
+    NotGuillotine { box_idx: usize },
+    // Box type is unknown or its dimensions differ from the box
+    WrongBoxType { box_idx: usize },
+    // More boxes of the type than available
+    Unavailable(usize),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::OutOfBounds { box_idx, rect_id } => write!(f, "Rectangle {} exceeds box {}", rect_id, box_idx),
+            ValidationError::Overlap { box_idx, first, second } => write!(f, "Rectangles {} and {} overlap in box {}", first, second, box_idx),
+            ValidationError::MissingRect(id) => write!(f, "Rectangle {} is not placed", id),
+            ValidationError::DuplicateRect(id) => write!(f, "Rectangle {} is placed more than once", id),
+            ValidationError::UnknownRect(id) => write!(f, "Rectangle {} is not part of the instance", id),
+            ValidationError::RotationNotAllowed(id) => write!(f, "Rectangle {} is placed in a forbidden orientation", id),
+            ValidationError::NotGuillotine { box_idx } => write!(f, "Box {} is not guillotine cuttable", box_idx),
+            ValidationError::WrongBoxType { box_idx } => write!(f, "Box {} doesn't match its box type", box_idx),
+            ValidationError::Unavailable(box_type) => write!(f, "More boxes of type {} than available", box_type),
+        }
+    }
+}
+
+/// Check that every rect is placed once in an allowed orientation inside its box without overlap and every box has
+/// the dimensions of an available box type, in guillotine mode also that every box is guillotine cuttable
+pub fn validate(solution: &RectangleSolution) -> Result<(), ValidationError> {
+    let instance = &solution.instance;
+    let mut placed = vec![false; instance.rects.len()];
+    let mut opened = vec![0; instance.box_types.len()];
+
+    for (box_idx, bin) in solution.boxes.iter().enumerate() {
+        let Some(box_type) = instance.box_types.get(bin.box_type) else {
+            return Err(ValidationError::WrongBoxType { box_idx });
+        };
+        if (box_type.width, box_type.height) != (bin.width, bin.height) {
+            return Err(ValidationError::WrongBoxType { box_idx });
+        }
+        opened[bin.box_type] += 1;
+        if box_type.available.is_some_and(|n| opened[bin.box_type] > n) {
+            return Err(ValidationError::Unavailable(bin.box_type));
+        }
+        for (i, p) in bin.placements().iter().enumerate() {
+            let rect_id = p.rect.id;
+            if p.x + p.width() > bin.width || p.y + p.height() > bin.height {
+                return Err(ValidationError::OutOfBounds { box_idx, rect_id });
+            }
+            if let Some(other) = bin.placements()[i + 1..].iter().find(|other| p.intersects(other)) {
+                return Err(ValidationError::Overlap { box_idx, first: rect_id, second: other.rect.id });
+            }
+            let Some(pos) = instance.rects.iter().position(|r| r.id == rect_id) else {
+                return Err(ValidationError::UnknownRect(rect_id));
+            };
+            if placed[pos] {
+                return Err(ValidationError::DuplicateRect(rect_id));
+            }
+            if !instance.rects[pos].allows(p.rotated) {
+                return Err(ValidationError::RotationNotAllowed(rect_id));
+            }
+            placed[pos] = true;
+        }
+        if instance.guillotine && !bin.is_guillotine() {
+            return Err(ValidationError::NotGuillotine { box_idx });
+        }
+    }
+    match placed.iter().position(|&p| !p) {
+        Some(pos) => Err(ValidationError::MissingRect(instance.rects[pos].id)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::instance::Instance;
+    use crate::problem::rect::Rect;
+    use crate::problem::solution::Placement;
+    use std::sync::Arc;
+
+    // Solution for the rects with the boxes given as placements (rect id, x, y), no rect is rotated
+    fn solution(instance: Instance, boxes: &[&[(usize, u32, u32)]]) -> RectangleSolution {
+        let instance = Arc::new(instance);
+        let mut solution = RectangleSolution::new(Arc::clone(&instance));
+        for placements in boxes {
+            let mut bin = instance.new_box(0);
+            for &(id, x, y) in placements.iter() {
+                let rect = *instance.rects.iter().find(|r| r.id == id).unwrap();
+                bin.place(Placement { rect, x, y, rotated: false });
+            }
+            solution.add_box(bin);
+        }
+        solution
+    }
+
+    fn squares(n: usize) -> Vec<Rect> {
+        (0..n).map(|id| Rect::new(id, 5, 5)).collect()
+    }
+
+    #[test]
+    fn valid_solution() {
+        let sol = solution(Instance::new(10, 10, squares(3)), &[&[(0, 0, 0), (1, 5, 0)], &[(2, 0, 0)]]);
+        assert_eq!(validate(&sol), Ok(()));
+    }
+
+    #[test]
+    fn overlap() {
+        let sol = solution(Instance::new(10, 10, squares(2)), &[&[(0, 0, 0), (1, 3, 3)]]);
+        assert_eq!(validate(&sol), Err(ValidationError::Overlap { box_idx: 0, first: 0, second: 1 }));
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let sol = solution(Instance::new(10, 10, squares(2)), &[&[(0, 0, 0)], &[(1, 6, 0)]]);
+        assert_eq!(validate(&sol), Err(ValidationError::OutOfBounds { box_idx: 1, rect_id: 1 }));
+    }
+
+    #[test]
+    fn duplicate_rect() {
+        let sol = solution(Instance::new(10, 10, squares(2)), &[&[(0, 0, 0), (1, 5, 0)], &[(1, 0, 0)]]);
+        assert_eq!(validate(&sol), Err(ValidationError::DuplicateRect(1)));
+    }
+
+    #[test]
+    fn missing_rect() {
+        let sol = solution(Instance::new(10, 10, squares(3)), &[&[(0, 0, 0), (2, 5, 0)]]);
+        assert_eq!(validate(&sol), Err(ValidationError::MissingRect(1)));
+    }
+
+    #[test]
+    fn not_guillotine_names_the_box() {
+        // Pinwheel of four 2x1 rects around a 1x1 rect in the second box
+        let mut rects: Vec<Rect> = [(2, 1), (1, 2), (2, 1), (1, 2), (1, 1)].iter().enumerate()
+            .map(|(id, &(w, h))| Rect::new(id, w, h))
+            .collect();
+        rects.push(Rect::new(5, 3, 1));
+        rects.push(Rect::new(6, 3, 2));
+        let pinwheel: &[(usize, u32, u32)] = &[(0, 0, 0), (1, 2, 0), (2, 1, 2), (3, 0, 1), (4, 1, 1)];
+        let stacked: &[(usize, u32, u32)] = &[(5, 0, 0), (6, 0, 1)];
+
+        let instance = Instance::new(3, 3, rects);
+        assert_eq!(validate(&solution(instance.clone(), &[stacked, pinwheel])), Ok(()));
+        let sol = solution(instance.with_guillotine(true), &[stacked, pinwheel]);
+        assert_eq!(validate(&sol), Err(ValidationError::NotGuillotine { box_idx: 1 }));
+    }
+}
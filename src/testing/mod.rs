@@ -1,21 +1,67 @@
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use rand::seq::SliceRandom;
 use rand::rng;
 
 use crate::algorithms;
-use crate::algorithms::traits::Solution;
+use crate::algorithms::traits::{Neighborhood, Solution};
+use crate::algorithms::local_search::{MoveSearchRun, PivotRule};
+use crate::algorithms::simulated_annealing::{AnnealingConfig, LexicographicEnergy};
+use crate::algorithms::iterated_local_search::IlsConfig;
+use crate::algorithms::genetic::GeneticConfig;
+use crate::algorithms::brkga::BrkgaConfig;
+use crate::algorithms::alns::AlnsConfig;
+use crate::algorithms::beam_search::BeamConfig;
+use crate::algorithms::variable_neighborhood_search::{self, VnsConfig};
+use crate::algorithms::grasp::{GraspConfig, RestrictedCandidateStrategy};
 use crate::generator::Generator;
-use crate::problem::instance::Instance;
+use crate::problem::instance::{BoxType, Instance};
+use crate::problem::rect::Rect;
+use crate::problem::beam_search::area_lower_bound;
+use crate::problem::bounds::LowerBounds;
+use crate::problem::branch_and_bound;
 use crate::problem::greedy::{RectangleGreedyState, SortByAreaStrategy, SortByMaxSideStrategy};
-use crate::problem::local_search::{GeometricNeighborhood, RuleBasedNeighborhood, OverlappingNeighborhood};
-use crate::problem::solution::{RectangleSolution, PermutationSolution, BoxBin};
+use crate::problem::local_search::{GeometricNeighborhood, BoxTypeNeighborhood, RuleBasedNeighborhood, OverlappingNeighborhood};
+use crate::problem::perturbation::ScatterRects;
+use crate::problem::genetic::{random_population, OrderCrossover, SwapMutation};
+use crate::problem::brkga::RotationKeySolution;
+use crate::problem::alns::{RandomRemoval, WorstBoxRemoval, RelatedSizeRemoval, SpatialClusterRemoval, FirstFitRepair, FullestBoxFirstRepair};
+use crate::problem::guillotine::GuillotineSplit;
+use crate::problem::maxrects::MaxRectsHeuristic;
+use crate::problem::validation::validate;
+use crate::problem::solution::{RectangleSolution, PermutationSolution, PlacementRule, BoxSelection};
+
+// Largest instances solved with Branch and Bound
+const EXACT_MAX_RECTS: usize = 30;
 
 pub struct TestConfig {
     pub num_instances: usize,
     pub num_rects: usize,
     pub width_range: (u32, u32),
     pub height_range: (u32, u32),
-    pub box_size: u32,
+    pub box_width: u32,
+    pub box_height: u32,
+}
+
+pub struct RotationConfig {
+    pub num_instances: usize,
+    pub num_rects: usize,
+    pub width_range: (u32, u32),
+    pub height_range: (u32, u32),
+    pub box_width: u32,
+    pub box_height: u32,
+    // Shares of rects that must not be rotated and that must be rotated, the others are free
+    pub fixed_share: f64,
+    pub rotated_share: f64,
+}
+
+pub struct BoxTypeConfig {
+    pub num_instances: usize,
+    pub num_rects: usize,
+    pub width_range: (u32, u32),
+    pub height_range: (u32, u32),
+    // Rects are generated to fit in the first box type
+    pub box_types: Vec<BoxType>,
 }
 
 /// Run testsuite with given configuration
@@ -23,34 +69,81 @@ pub fn run_suite(configs: &[TestConfig]) {
     println!("Start Test Suite");
     
     for config in configs {
-        println!("\nConfiguration: {} Rectangles, Box-Size L={}, Rectangle Ranges (width)-(height) {:?}-{:?}", 
-            config.num_rects, config.box_size, config.width_range, config.height_range);
+        println!("\nConfiguration: {} Rectangles, Box-Size {}x{}, Rectangle Ranges (width)-(height) {:?}-{:?}", 
+            config.num_rects, config.box_width, config.box_height, config.width_range, config.height_range);
         println!("Number Instances: {}", config.num_instances);
         
-        println!("\n{:<25} | {:<12} | {:<15}", "Algorithm", "Ø Boxes", "Ø Time (ms)");
-        println!("{:-<58}", "-");
+        println!("\n{:<25} | {:<12} | {:<15} | {:<12}", "Algorithm", "Ø Boxes", "Ø Time (ms)", "Ø Gap (%)");
+        println!("{:-<73}", "-");
+
+        let mut bounds = Vec::new();
 
         let mut results_greedy_area = Vec::new();
         let mut results_greedy_side = Vec::new();
         let mut results_ls_geo = Vec::new();
         let mut results_ls_rule = Vec::new();
         let mut results_ls_overlap = Vec::new();
+        let mut results_sa_geo = Vec::new();
+        let mut results_ils_geo = Vec::new();
+        let mut results_ga_perm = Vec::new();
+        let mut results_brkga = Vec::new();
+        let mut results_alns = Vec::new();
+        let mut results_grasp = Vec::new();
+        let mut results_beam = Vec::new();
+        let mut results_exact = Vec::new();
+        let mut results_guillotine_greedy = Vec::new();
+        let mut results_guillotine_ls = Vec::new();
+        // Geometric Local Search with other pivot rules than first improvement
+        let pivot_rules = [PivotRule::BestImprovement, PivotRule::RandomFirstImprovement, PivotRule::BestOfSampled(20)];
+        let mut results_ls_pivot: Vec<Vec<(usize, Duration)>> = vec![Vec::new(); pivot_rules.len()];
+        // Greedy with MaxRects and Skyline placement instead of candidate points
+        let placement_rules = [
+            MaxRectsHeuristic::BestShortSideFit, MaxRectsHeuristic::BestLongSideFit, MaxRectsHeuristic::BestAreaFit,
+            MaxRectsHeuristic::BottomLeft, MaxRectsHeuristic::ContactPoint,
+        ].map(PlacementRule::MaxRects).into_iter().chain([PlacementRule::Skyline]).collect::<Vec<_>>();
+        let mut results_greedy_placement: Vec<Vec<(usize, Duration)>> = vec![Vec::new(); placement_rules.len()];
 
         for _ in 0..config.num_instances {
-            // Generate Instances
-            let instance = Generator::generate_instance(config.num_rects, config.width_range, config.height_range, config.box_size);
+            // Generate Instances, shared by all solutions
+            let instance = Arc::new(Generator::generate_instance(config.num_rects, config.width_range, config.height_range, config.box_width, config.box_height));
+            bounds.push(LowerBounds::new(&instance));
             
             // Greedy (Area)
             let start = Instant::now();
-            let mut state = RectangleGreedyState::new(instance.clone());
+            let mut state = RectangleGreedyState::new(Arc::clone(&instance));
             let mut strat = SortByAreaStrategy;
             algorithms::greedy::solve(&mut state, &mut strat);
             let dur = start.elapsed();
             results_greedy_area.push((state.solution.boxes.len(), dur));
 
+            // Greedy (Area) with other placement rules
+            for (rule, results) in placement_rules.iter().zip(results_greedy_placement.iter_mut()) {
+                let start = Instant::now();
+                let mut state = RectangleGreedyState::new(Arc::clone(&instance)).with_placement(*rule);
+                algorithms::greedy::solve(&mut state, &mut SortByAreaStrategy);
+                let dur = start.elapsed();
+                results.push((state.solution.boxes.len(), dur));
+            }
+
+            // Guillotine mode: Greedy with guillotine splits, then geometric Local Search
+            let guillotine_instance = Arc::new(Instance::clone(&instance).with_guillotine(true));
+            let start = Instant::now();
+            let mut state = RectangleGreedyState::new(Arc::clone(&guillotine_instance))
+                .with_placement(PlacementRule::Guillotine(GuillotineSplit::default()));
+            algorithms::greedy::solve(&mut state, &mut SortByAreaStrategy);
+            let dur = start.elapsed();
+            results_guillotine_greedy.push((state.solution.boxes.len(), dur));
+            let start = Instant::now();
+            let sol_guillotine = algorithms::local_search::solve_moves(state.solution, &GeometricNeighborhood);
+            let dur = dur + start.elapsed();
+            if let Err(err) = validate(&sol_guillotine) {
+                panic!("Invalid guillotine solution: {}", err);
+            }
+            results_guillotine_ls.push((sol_guillotine.boxes.len(), dur));
+
             // Greedy (Max Side)
             let start = Instant::now();
-            let mut state = RectangleGreedyState::new(instance.clone());
+            let mut state = RectangleGreedyState::new(Arc::clone(&instance));
             let mut strat = SortByMaxSideStrategy;
             algorithms::greedy::solve(&mut state, &mut strat);
             let dur = start.elapsed();
@@ -62,19 +155,27 @@ pub fn run_suite(configs: &[TestConfig]) {
             // Local Search Geometric
             let start = Instant::now();
             let neigh_geo = GeometricNeighborhood;
-            let sol_geo = algorithms::local_search::solve(trivial_sol.clone(), &neigh_geo);
+            let sol_geo = algorithms::local_search::solve_moves(trivial_sol.clone(), &neigh_geo);
             let dur = start.elapsed();
             results_ls_geo.push((sol_geo.boxes.len(), dur));
 
+            // Local Search Geometric with pivot rules
+            for (rule, results) in pivot_rules.iter().zip(results_ls_pivot.iter_mut()) {
+                let start = Instant::now();
+                let sol = MoveSearchRun::new(trivial_sol.clone(), &neigh_geo).with_pivot_rule(*rule).run();
+                let dur = start.elapsed();
+                results.push((sol.boxes.len(), dur));
+            }
+
             // Local Search Rule Based
             // Start with random permutation
             let mut rects_perm = instance.rects.clone();
             rects_perm.shuffle(&mut rng());
-            let start_perm = PermutationSolution::new(instance.clone(), rects_perm);
+            let start_perm = PermutationSolution::new(Arc::clone(&instance), rects_perm);
             
             let start = Instant::now();
             let neigh_rule = RuleBasedNeighborhood::new(Some(50)); 
-            let sol_perm = algorithms::local_search::solve(start_perm, &neigh_rule);
+            let sol_perm = algorithms::local_search::solve_moves(start_perm, &neigh_rule);
             let dur = start.elapsed();
             results_ls_rule.push((sol_perm.cost().0, dur));
 
@@ -83,24 +184,267 @@ pub fn run_suite(configs: &[TestConfig]) {
             let sol_overlap = run_overlapping_ls(trivial_sol.clone());
             let dur = start.elapsed();
             results_ls_overlap.push((sol_overlap.boxes.len(), dur));
+
+            // Simulated Annealing Geometric
+            let start = Instant::now();
+            let energy = LexicographicEnergy::new(instance.box_area());
+            let sol_sa = algorithms::simulated_annealing::solve(trivial_sol.clone(), &neigh_geo, &energy, &AnnealingConfig::default());
+            let dur = start.elapsed();
+            results_sa_geo.push((sol_sa.boxes.len(), dur));
+
+            // Iterated Local Search Geometric
+            let start = Instant::now();
+            let ils_config = IlsConfig { max_iterations: 20, ..IlsConfig::default() };
+            let sol_ils = algorithms::iterated_local_search::solve(trivial_sol.clone(), &neigh_geo, &ScatterRects { k: 5 }, &ils_config);
+            let dur = start.elapsed();
+            results_ils_geo.push((sol_ils.boxes.len(), dur));
+
+            // Genetic Algorithm Permutation
+            let start = Instant::now();
+            let population = random_population(&instance, 20);
+            let ga_config = GeneticConfig { generations: 25, ..GeneticConfig::default() };
+            let sol_ga = algorithms::genetic::solve(population, &OrderCrossover, &SwapMutation, &ga_config);
+            let dur = start.elapsed();
+            results_ga_perm.push((sol_ga.cost().0, dur));
+
+            // BRKGA with rotation keys
+            let start = Instant::now();
+            let brkga_config = BrkgaConfig { population_size: 20, generations: 25, ..BrkgaConfig::default() };
+            let sol_brkga = algorithms::brkga::solve(&RotationKeySolution::new(Arc::clone(&instance)), &brkga_config);
+            let dur = start.elapsed();
+            results_brkga.push((sol_brkga.cost().0, dur));
+
+            // Adaptive Large Neighborhood Search
+            let start = Instant::now();
+            let sol_alns = algorithms::alns::solve(
+                trivial_sol.clone(),
+                &[&RandomRemoval { k: 10 }, &WorstBoxRemoval { boxes: 2 }, &RelatedSizeRemoval { k: 10 }, &SpatialClusterRemoval { k: 5 }],
+                &[&FirstFitRepair, &FullestBoxFirstRepair],
+                &energy,
+                &AlnsConfig::default(),
+            );
+            let dur = start.elapsed();
+            results_alns.push((sol_alns.boxes.len(), dur));
+
+            // GRASP with area scores and geometric Local Search
+            let start = Instant::now();
+            let mut rcl = RestrictedCandidateStrategy::new(0.2, |r: &Rect| r.area() as f64);
+            let initial = RectangleGreedyState::new(Arc::clone(&instance));
+            let sol_grasp = algorithms::grasp::solve(&initial, &mut rcl, |state| state.solution, &neigh_geo, &GraspConfig { starts: 5 });
+            let dur = start.elapsed();
+            results_grasp.push((sol_grasp.boxes.len(), dur));
+
+            // Beam search with area lower bound
+            let start = Instant::now();
+            let sol_beam = algorithms::beam_search::solve(&initial, |r: &Rect| r.area() as f64, area_lower_bound, &BeamConfig::default());
+            let dur = start.elapsed();
+            results_beam.push((sol_beam.solution.boxes.len(), dur));
+
+            // Branch and Bound, only for small instances
+            if config.num_rects <= EXACT_MAX_RECTS {
+                let start = Instant::now();
+                let sol_exact = branch_and_bound::solve(Arc::clone(&instance), Duration::from_secs(2));
+                let dur = start.elapsed();
+                results_exact.push((sol_exact.solution.boxes.len(), dur));
+            }
+        }
+
+        print_bounds(&bounds);
+        print_stats("Greedy SortByArea", &results_greedy_area, &bounds);
+        for (rule, results) in placement_rules.iter().zip(&results_greedy_placement) {
+            print_stats(&format!("Greedy {}", rule), results, &bounds);
+        }
+        print_stats("Greedy SortByMaxSide", &results_greedy_side, &bounds);
+        print_stats("Greedy Guillotine", &results_guillotine_greedy, &bounds);
+        print_stats("Greedy + LS Guillotine", &results_guillotine_ls, &bounds);
+        print_stats("Local Search Geometric", &results_ls_geo, &bounds);
+        for (rule, results) in pivot_rules.iter().zip(&results_ls_pivot) {
+            print_stats(&format!("LS Geometric {}", rule), results, &bounds);
+        }
+        print_stats("Local Search Permutation", &results_ls_rule, &bounds);
+        print_stats("Local Search Overlap", &results_ls_overlap, &bounds);
+        print_stats("Simulated Annealing Geo", &results_sa_geo, &bounds);
+        print_stats("Iterated LS Geometric", &results_ils_geo, &bounds);
+        print_stats("Genetic Permutation OX", &results_ga_perm, &bounds);
+        print_stats("BRKGA Rotation Keys", &results_brkga, &bounds);
+        print_stats("ALNS", &results_alns, &bounds);
+        print_stats("GRASP Area", &results_grasp, &bounds);
+        print_stats("Beam Search Area Bound", &results_beam, &bounds);
+        print_stats("Branch and Bound (2s)", &results_exact, &bounds);
+    }
+}
+
+/// Run testsuite for instances with fixed and rotated rects, every solution is validated
+pub fn run_rotation_suite(configs: &[RotationConfig]) {
+    println!("Start Rotation Test Suite");
+
+    for config in configs {
+        println!("\nConfiguration: {} Rectangles, Box-Size {}x{}, Rectangle Ranges (width)-(height) {:?}-{:?}, Fixed {:.0}%, Rotated {:.0}%",
+            config.num_rects, config.box_width, config.box_height, config.width_range, config.height_range,
+            100.0 * config.fixed_share, 100.0 * config.rotated_share);
+        println!("Number Instances: {}", config.num_instances);
+
+        println!("\n{:<25} | {:<12} | {:<15} | {:<12}", "Algorithm", "Ø Boxes", "Ø Time (ms)", "Ø Gap (%)");
+        println!("{:-<73}", "-");
+
+        let mut bounds = Vec::new();
+        let placement_rules = [
+            PlacementRule::default(), PlacementRule::MaxRects(MaxRectsHeuristic::BestShortSideFit),
+            PlacementRule::Skyline, PlacementRule::Guillotine(GuillotineSplit::default()),
+        ];
+        let mut results_greedy: Vec<Vec<(usize, Duration)>> = vec![Vec::new(); placement_rules.len()];
+        let mut results_ls_geo = Vec::new();
+        let mut results_alns = Vec::new();
+        let mut results_brkga = Vec::new();
+        let mut results_exact = Vec::new();
+
+        for _ in 0..config.num_instances {
+            let instance = Arc::new(Generator::generate_mixed_rotation(config.num_rects, config.width_range, config.height_range,
+                config.box_width, config.box_height, config.fixed_share, config.rotated_share));
+            bounds.push(LowerBounds::new(&instance));
+
+            // Greedy (Area) with each placement rule
+            for (rule, results) in placement_rules.iter().zip(results_greedy.iter_mut()) {
+                let start = Instant::now();
+                let mut state = RectangleGreedyState::new(Arc::clone(&instance)).with_placement(*rule);
+                algorithms::greedy::solve(&mut state, &mut SortByAreaStrategy);
+                let dur = start.elapsed();
+                results.push((validated_boxes(&state.solution), dur));
+            }
+
+            // Local Search Geometric
+            let trivial_sol = create_trivial_solution(&instance);
+            let start = Instant::now();
+            let sol_ls = algorithms::local_search::solve_moves(trivial_sol.clone(), &GeometricNeighborhood);
+            let dur = start.elapsed();
+            results_ls_geo.push((validated_boxes(&sol_ls), dur));
+
+            // Adaptive Large Neighborhood Search
+            let start = Instant::now();
+            let sol_alns = algorithms::alns::solve(
+                trivial_sol,
+                &[&RandomRemoval { k: 10 }, &WorstBoxRemoval { boxes: 2 }],
+                &[&FirstFitRepair, &FullestBoxFirstRepair],
+                &LexicographicEnergy::new(instance.box_area()),
+                &AlnsConfig::default(),
+            );
+            let dur = start.elapsed();
+            results_alns.push((validated_boxes(&sol_alns), dur));
+
+            // BRKGA, fixed and rotated rects ignore their rotation keys
+            let start = Instant::now();
+            let brkga_config = BrkgaConfig { population_size: 20, generations: 25, ..BrkgaConfig::default() };
+            let sol_brkga = algorithms::brkga::solve(&RotationKeySolution::new(Arc::clone(&instance)), &brkga_config);
+            let dur = start.elapsed();
+            results_brkga.push((validated_boxes(&sol_brkga.to_rectangle_solution()), dur));
+
+            // Branch and Bound, only for small instances
+            if config.num_rects <= EXACT_MAX_RECTS {
+                let start = Instant::now();
+                let sol_exact = branch_and_bound::solve(Arc::clone(&instance), Duration::from_secs(2));
+                let dur = start.elapsed();
+                results_exact.push((validated_boxes(&sol_exact.solution), dur));
+            }
         }
 
-        print_stats("Greedy SortByArea", &results_greedy_area);
-        print_stats("Greedy SortByMaxSide", &results_greedy_side);
-        print_stats("Local Search Geometric", &results_ls_geo);
-        print_stats("Local Search Permutation", &results_ls_rule);
-        print_stats("Local Search Overlap", &results_ls_overlap);
+        print_bounds(&bounds);
+        for (rule, results) in placement_rules.iter().zip(&results_greedy) {
+            print_stats(&format!("Greedy {}", rule), results, &bounds);
+        }
+        print_stats("Local Search Geometric", &results_ls_geo, &bounds);
+        print_stats("ALNS", &results_alns, &bounds);
+        print_stats("BRKGA Rotation Keys", &results_brkga, &bounds);
+        print_stats("Branch and Bound (2s)", &results_exact, &bounds);
+    }
+}
+
+// Number of boxes of a validated solution
+fn validated_boxes(solution: &RectangleSolution) -> usize {
+    if let Err(err) = validate(solution) {
+        panic!("Invalid solution: {}", err);
     }
+    solution.boxes.len()
+}
+
+/// Run testsuite for instances with several box types, the cost is the total cost of the opened boxes
+pub fn run_box_type_suite(configs: &[BoxTypeConfig]) {
+    println!("Start Box Type Test Suite");
+
+    for config in configs {
+        let types: Vec<String> = config.box_types.iter().map(|t| format!("{}x{} ({})", t.width, t.height, t.cost)).collect();
+        println!("\nConfiguration: {} Rectangles, Box Types (cost) {}, Rectangle Ranges (width)-(height) {:?}-{:?}",
+            config.num_rects, types.join(", "), config.width_range, config.height_range);
+        println!("Number Instances: {}", config.num_instances);
+
+        println!("\n{:<25} | {:<12} | {:<12} | {:<15}", "Algorithm", "Ø Cost", "Ø Boxes", "Ø Time (ms)");
+        println!("{:-<73}", "-");
+
+        let selections = [BoxSelection::CostPerArea, BoxSelection::Cheapest, BoxSelection::Largest];
+        let mut results_greedy: Vec<Vec<(usize, usize, Duration)>> = vec![Vec::new(); selections.len()];
+        let mut results_ls_geo = Vec::new();
+        let mut results_vnd = Vec::new();
+        let mut results_vns = Vec::new();
+        // Moving rects and changing box types
+        let neighborhoods: [&dyn Neighborhood<RectangleSolution>; 2] = [&GeometricNeighborhood, &BoxTypeNeighborhood];
+
+        for _ in 0..config.num_instances {
+            let BoxType { width, height, .. } = config.box_types[0];
+            let instance = Generator::generate_instance(config.num_rects, config.width_range, config.height_range, width, height);
+            let instance = Arc::new(instance.with_box_types(config.box_types.clone()));
+
+            // Greedy (Area) with each box selection
+            for (selection, results) in selections.iter().zip(results_greedy.iter_mut()) {
+                let start = Instant::now();
+                let mut state = RectangleGreedyState::new(Arc::clone(&instance)).with_box_selection(*selection);
+                algorithms::greedy::solve(&mut state, &mut SortByAreaStrategy);
+                let dur = start.elapsed();
+                results.push(cost_result(&state.solution, dur));
+            }
+
+            // Improvement methods start from the greedy solution with the default box selection
+            let mut state = RectangleGreedyState::new(Arc::clone(&instance));
+            algorithms::greedy::solve(&mut state, &mut SortByAreaStrategy);
+            let start_sol = state.solution;
+
+            // Local Search Geometric, keeps the box types of the greedy solution
+            let start = Instant::now();
+            let sol_ls = algorithms::local_search::solve_moves(start_sol.clone(), &GeometricNeighborhood);
+            results_ls_geo.push(cost_result(&sol_ls, start.elapsed()));
+
+            // VND over rect moves and box type changes
+            let start = Instant::now();
+            let sol_vnd = variable_neighborhood_search::vnd(start_sol.clone(), &neighborhoods);
+            results_vnd.push(cost_result(&sol_vnd, start.elapsed()));
+
+            // VNS shaking in both neighborhoods
+            let start = Instant::now();
+            let sol_vns = variable_neighborhood_search::solve(start_sol, &neighborhoods, &VnsConfig::default());
+            results_vns.push(cost_result(&sol_vns, start.elapsed()));
+        }
+
+        for (selection, results) in selections.iter().zip(&results_greedy) {
+            print_cost_stats(&format!("Greedy {}", selection), results);
+        }
+        print_cost_stats("Local Search Geometric", &results_ls_geo);
+        print_cost_stats("VND Geometric + Type", &results_vnd);
+        print_cost_stats("VNS Geometric + Type", &results_vns);
+    }
+}
+
+// Total box cost and number of boxes of a validated solution
+fn cost_result(solution: &RectangleSolution, dur: Duration) -> (usize, usize, Duration) {
+    if let Err(err) = validate(solution) {
+        panic!("Invalid solution: {}", err);
+    }
+    (solution.box_cost(), solution.boxes.len(), dur)
 }
 
 /// Create trivial solution: each rectangle in one box
-fn create_trivial_solution(instance: &Instance) -> RectangleSolution {
-    let mut sol = RectangleSolution::new(instance.clone());
+fn create_trivial_solution(instance: &Arc<Instance>) -> RectangleSolution {
+    let mut sol = RectangleSolution::new(Arc::clone(instance));
     for r in &instance.rects {
-        let mut b = BoxBin::new(instance.box_size);
         // Place it at left-bottom
-        b.try_place(*r, 0, 0, false); 
-        sol.boxes.push(b);
+        sol.open_box(*r);
     }
     sol
 }
@@ -116,7 +460,7 @@ fn run_overlapping_ls(start_sol: RectangleSolution) -> RectangleSolution {
     for _ in 0..steps {
         let neigh = OverlappingNeighborhood { max_overlap_percent: percent };
         // Local Search for this level
-        current_sol = algorithms::local_search::solve(current_sol, &neigh);
+        current_sol = algorithms::local_search::solve_moves(current_sol, &neigh);
         // Tighten parameter
         percent -= 1.0 / (steps as f64);
         if percent < 0.0 { percent = 0.0; }
@@ -129,16 +473,35 @@ fn run_overlapping_ls(start_sol: RectangleSolution) -> RectangleSolution {
     let mut strict_sol = current_sol;
     strict_sol.penalty_factor = None; 
     
-    algorithms::local_search::solve(strict_sol, &GeometricNeighborhood)
+    algorithms::local_search::solve_moves(strict_sol, &GeometricNeighborhood)
+}
+
+/// Helping function to print average lower bounds
+fn print_bounds(bounds: &[LowerBounds]) {
+    if bounds.is_empty() { return; }
+
+    let avg = |bound: fn(&LowerBounds) -> usize| bounds.iter().map(|b| bound(b) as f64).sum::<f64>() / bounds.len() as f64;
+    println!("{:<25} | {:<12.2} | L0 {:.2}, L1 {:.2}, L2 {:.2}", "Lower Bound", avg(LowerBounds::best), avg(|b| b.l0), avg(|b| b.l1), avg(|b| b.l2));
+}
+
+/// Helping function to print statistics of instances with several box types
+fn print_cost_stats(name: &str, results: &[(usize, usize, Duration)]) {
+    if results.is_empty() { return; }
+
+    let avg = |value: fn(&(usize, usize, Duration)) -> f64| results.iter().map(value).sum::<f64>() / results.len() as f64;
+    println!("{:<25} | {:<12.2} | {:<12.2} | {:<15.2}", name, avg(|r| r.0 as f64), avg(|r| r.1 as f64), avg(|r| r.2.as_millis() as f64));
 }
 
-/// Helping function to print statistics
-fn print_stats(name: &str, results: &[(usize, Duration)]) {
+/// Helping function to print statistics, gap is relative to the best lower bound of each instance
+fn print_stats(name: &str, results: &[(usize, Duration)], bounds: &[LowerBounds]) {
     if results.is_empty() { return; }
     
     let avg_boxes: f64 = results.iter().map(|r| r.0 as f64).sum::<f64>() / results.len() as f64;
     let avg_time: f64 = results.iter().map(|r| r.1.as_millis() as f64).sum::<f64>() / results.len() as f64;
+    let avg_gap: f64 = results.iter().zip(bounds)
+        .map(|(r, b)| 100.0 * (r.0 as f64 - b.best() as f64) / b.best().max(1) as f64)
+        .sum::<f64>() / results.len() as f64;
     
-    println!("{:<25} | {:<12.2} | {:<15.2}", name, avg_boxes, avg_time);
+    println!("{:<25} | {:<12.2} | {:<15.2} | {:<12.2}", name, avg_boxes, avg_time, avg_gap);
 }
 